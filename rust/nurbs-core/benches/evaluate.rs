@@ -0,0 +1,116 @@
+//! Span-local vs dense surface evaluation on a 50x50 bicubic patch
+//!
+//! Run with `cargo bench -p nurbs-core --bench evaluate`.
+
+#![allow(clippy::needless_range_loop)]
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ndarray::{Array2, Array3};
+use nurbs_core::{CoxDeBoor, NURBSSurface};
+use rayon::prelude::*;
+
+const RES: usize = 50;
+const DEGREE: usize = 3;
+
+fn create_patch() -> NURBSSurface {
+    let mut control_points = Array3::zeros((RES, RES, 3));
+    let mut weights = Array2::ones((RES, RES));
+    for i in 0..RES {
+        for j in 0..RES {
+            let x = i as f64 / (RES - 1) as f64;
+            let y = j as f64 / (RES - 1) as f64;
+            control_points[[i, j, 0]] = x;
+            control_points[[i, j, 1]] = y;
+            control_points[[i, j, 2]] = 0.1 * (6.0 * x).sin() * (4.0 * y).cos();
+            weights[[i, j]] = 1.0 + 0.25 * ((i + j) % 2) as f64;
+        }
+    }
+
+    // Clamped uniform knots
+    let spans = RES - DEGREE;
+    let knots: Vec<f64> = (0..RES + DEGREE + 1)
+        .map(|k| (k.saturating_sub(DEGREE).min(spans)) as f64 / spans as f64)
+        .collect();
+
+    NURBSSurface::new(DEGREE, DEGREE, control_points, weights, knots.clone(), knots)
+}
+
+/// Previous evaluator: full-length basis vectors, sum over every control point
+fn evaluate_dense(surface: &NURBSSurface, u: f64, v: f64) -> [f64; 3] {
+    let (u_res, v_res) = surface.dimensions();
+    let mut basis_u = vec![0.0; u_res];
+    let mut basis_v = vec![0.0; v_res];
+    CoxDeBoor::evaluate_all(u, &surface.knots_u, surface.degree_u, &mut basis_u);
+    CoxDeBoor::evaluate_all(v, &surface.knots_v, surface.degree_v, &mut basis_v);
+
+    let mut weight_sum = 0.0;
+    let mut point = [0.0; 3];
+    for i in 0..u_res {
+        for j in 0..v_res {
+            let nw = basis_u[i] * basis_v[j] * surface.weights[[i, j]];
+            weight_sum += nw;
+            for k in 0..3 {
+                point[k] += nw * surface.control_points[[i, j, k]];
+            }
+        }
+    }
+
+    point.map(|c| c / weight_sum)
+}
+
+fn grid_params(samples: usize) -> Vec<[f64; 2]> {
+    let step = 1.0 / (samples - 1) as f64;
+    (0..samples)
+        .flat_map(|i| (0..samples).map(move |j| [i as f64 * step, j as f64 * step]))
+        .collect()
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let surface = create_patch();
+    let params = grid_params(100);
+
+    let mut group = c.benchmark_group("evaluate_batch");
+    group.bench_function(BenchmarkId::new("dense", params.len()), |b| {
+        b.iter(|| {
+            let points: Vec<[f64; 3]> = params
+                .par_iter()
+                .map(|&[u, v]| evaluate_dense(&surface, u, v))
+                .collect();
+            black_box(points)
+        })
+    });
+    group.bench_function(BenchmarkId::new("span_local", params.len()), |b| {
+        b.iter(|| black_box(surface.evaluate_batch(&params)))
+    });
+    group.finish();
+}
+
+fn bench_grid(c: &mut Criterion) {
+    let surface = create_patch();
+    let samples = 100;
+    let params = grid_params(samples);
+
+    let mut group = c.benchmark_group("evaluate_grid");
+    group.bench_function(BenchmarkId::new("dense", samples), |b| {
+        b.iter(|| {
+            let mut grid = Array3::<f64>::zeros((samples, samples, 3));
+            let points: Vec<[f64; 3]> = params
+                .par_iter()
+                .map(|&[u, v]| evaluate_dense(&surface, u, v))
+                .collect();
+            for (idx, point) in points.iter().enumerate() {
+                for k in 0..3 {
+                    grid[[idx / samples, idx % samples, k]] = point[k];
+                }
+            }
+            black_box(grid)
+        })
+    });
+    group.bench_function(BenchmarkId::new("span_local", samples), |b| {
+        b.iter(|| black_box(surface.evaluate_grid(samples, samples)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch, bench_grid);
+criterion_main!(benches);
@@ -3,6 +3,7 @@ pub struct CoxDeBoor;
 
 impl CoxDeBoor {
     /// Evaluate all non-zero basis functions at parameter t
+    #[allow(clippy::needless_range_loop)]
     pub fn evaluate_all(t: f64, knots: &[f64], degree: usize, output: &mut [f64]) {
         let n = knots.len() - degree - 1;
         assert_eq!(output.len(), n);
@@ -29,17 +30,25 @@ impl CoxDeBoor {
     }
 
     /// Find knot span containing parameter t
-    fn find_span(t: f64, degree: usize, knots: &[f64]) -> usize {
+    pub fn find_span(t: f64, degree: usize, knots: &[f64]) -> usize {
         let n = knots.len() - degree - 1;
 
-        // Special case: t at upper bound
+        // Special case: t at upper bound, in the last non-empty span
         if t >= knots[n] {
-            return n - 1;
+            let mut span = n - 1;
+            while span > degree && knots[span] == knots[n] {
+                span -= 1;
+            }
+            return span;
         }
 
-        // Special case: t at lower bound
+        // Special case: t at lower bound, in the first non-empty span
         if t <= knots[degree] {
-            return degree;
+            let mut span = degree;
+            while span + 1 < n && knots[span + 1] == knots[degree] {
+                span += 1;
+            }
+            return span;
         }
 
         // Binary search
@@ -60,10 +69,25 @@ impl CoxDeBoor {
 
     /// Compute non-zero basis functions using Cox-de Boor recursion
     fn basis_funs(span: usize, t: f64, degree: usize, knots: &[f64], output: &mut [f64]) {
-        output[0] = 1.0;
-
         let mut left = vec![0.0; degree + 1];
         let mut right = vec![0.0; degree + 1];
+        Self::basis_funs_with(span, t, degree, knots, output, &mut left, &mut right);
+    }
+
+    /// Non-zero basis functions N_{span-p..=span}(t) without allocating
+    ///
+    /// `output`, `left` and `right` must hold at least degree + 1 entries;
+    /// `left` and `right` are scratch space.
+    pub fn basis_funs_with(
+        span: usize,
+        t: f64,
+        degree: usize,
+        knots: &[f64],
+        output: &mut [f64],
+        left: &mut [f64],
+        right: &mut [f64],
+    ) {
+        output[0] = 1.0;
 
         for j in 1..=degree {
             left[j] = t - knots[span + 1 - j];
@@ -82,6 +106,7 @@ impl CoxDeBoor {
     }
 
     /// Compute derivatives of basis functions (for tangent/normal computation)
+    #[allow(clippy::needless_range_loop)]
     pub fn evaluate_derivatives(
         t: f64,
         knots: &[f64],
@@ -151,8 +176,10 @@ impl CoxDeBoor {
                 };
 
                 for j in j1..j2 {
-                    a[s2][j] = (a[s1][j] - a[s1][j - 1]) / ndu[pk + 1][rk as usize + j];
-                    d += a[s2][j] * ndu[rk as usize + j][pk];
+                    // rk may be negative here; j >= -rk keeps the index in range
+                    let idx = (rk + j as isize) as usize;
+                    a[s2][j] = (a[s1][j] - a[s1][j - 1]) / ndu[pk + 1][idx];
+                    d += a[s2][j] * ndu[idx][pk];
                 }
 
                 if r <= pk {
@@ -192,6 +219,11 @@ mod tests {
         assert_eq!(CoxDeBoor::find_span(0.5, degree, &knots), 4);
         assert_eq!(CoxDeBoor::find_span(0.75, degree, &knots), 4);
         assert_eq!(CoxDeBoor::find_span(1.0, degree, &knots), 4);
+
+        // Unclamped knots with repeated domain ends skip the empty spans
+        let knots = vec![0.0, 1.0, 2.0, 3.0, 3.0, 4.0, 5.0, 5.0, 6.0, 7.0];
+        assert_eq!(CoxDeBoor::find_span(3.0, degree, &knots), 4);
+        assert_eq!(CoxDeBoor::find_span(5.0, degree, &knots), 5);
     }
 
     #[test]
@@ -209,4 +241,19 @@ mod tests {
         assert_relative_eq!(basis[1], 0.5, epsilon = 1e-10);
         assert_relative_eq!(basis[2], 0.25, epsilon = 1e-10);
     }
+
+    #[test]
+    fn test_derivatives_sum_to_zero() {
+        let knots = vec![0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0];
+        let degree = 3;
+
+        for t in [0.1, 0.5, 0.9] {
+            let mut ders = vec![vec![0.0; degree + 1]; 3];
+            CoxDeBoor::evaluate_derivatives(t, &knots, degree, 2, &mut ders);
+
+            // Derivatives of a partition of unity vanish
+            assert_relative_eq!(ders[1].iter().sum::<f64>(), 0.0, epsilon = 1e-10);
+            assert_relative_eq!(ders[2].iter().sum::<f64>(), 0.0, epsilon = 1e-10);
+        }
+    }
 }
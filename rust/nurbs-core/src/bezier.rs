@@ -0,0 +1,247 @@
+//! Bezier patch decomposition
+//!
+//! Every interior knot is raised to full multiplicity, after which each
+//! non-empty knot-span rectangle is an independent rational Bezier patch.
+
+use ndarray::s;
+
+use crate::refine::knot_multiplicity;
+use crate::surface::NURBSSurface;
+
+/// Rational Bezier patch of a decomposed surface
+#[derive(Debug, Clone)]
+pub struct BezierPatch {
+    /// Patch as a surface with (p+1) x (q+1) control points
+    pub surface: NURBSSurface,
+    /// Parameter interval of the patch in the original surface
+    pub u_range: [f64; 2],
+    pub v_range: [f64; 2],
+}
+
+impl NURBSSurface {
+    /// Split the surface into rational Bezier patches
+    ///
+    /// Returns a grid indexed as `patches[i][j]` for the i-th u span and j-th
+    /// v span. Each patch keeps its original parameter interval, so it
+    /// evaluates identically to the surface on that rectangle. Unclamped
+    /// surfaces are clamped first.
+    pub fn to_bezier_patches(&self) -> Vec<Vec<BezierPatch>> {
+        let p = self.degree_u;
+        let q = self.degree_v;
+
+        let clamped = self.clamped();
+        let refined = clamped
+            .refine_knots_u(&bezier_insertions(&clamped.knots_u, p))
+            .refine_knots_v(&bezier_insertions(&clamped.knots_v, q));
+
+        let us = breakpoints(&refined.knots_u, p);
+        let vs = breakpoints(&refined.knots_v, q);
+
+        // Each span's patch ends at the control point of its span index;
+        // interior knots may have multiplicity p or p + 1
+        let spans = |knots: &[f64], values: &[f64]| -> Vec<usize> {
+            values[..values.len() - 1]
+                .iter()
+                .map(|&t| knots.iter().rposition(|&k| k == t).expect("breakpoint is a knot"))
+                .collect()
+        };
+        let spans_u = spans(&refined.knots_u, &us);
+        let spans_v = spans(&refined.knots_v, &vs);
+
+        (0..us.len() - 1)
+            .map(|i| {
+                (0..vs.len() - 1)
+                    .map(|j| {
+                        let (u0, u1) = (us[i], us[i + 1]);
+                        let (v0, v1) = (vs[j], vs[j + 1]);
+                        let (a, b) = (spans_u[i] - p, spans_v[j] - q);
+
+                        let control_points = refined
+                            .control_points
+                            .slice(s![a..=a + p, b..=b + q, ..])
+                            .to_owned();
+                        let weights = refined.weights.slice(s![a..=a + p, b..=b + q]).to_owned();
+
+                        let knots_u = [vec![u0; p + 1], vec![u1; p + 1]].concat();
+                        let knots_v = [vec![v0; q + 1], vec![v1; q + 1]].concat();
+
+                        BezierPatch {
+                            surface: NURBSSurface::new(p, q, control_points, weights, knots_u, knots_v),
+                            u_range: [u0, u1],
+                            v_range: [v0, v1],
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Distinct knot values bounding the non-empty spans of the domain
+pub(crate) fn breakpoints(knots: &[f64], degree: usize) -> Vec<f64> {
+    let n = knots.len() - degree - 1;
+    let mut values = knots[degree..=n].to_vec();
+    values.dedup();
+    values
+}
+
+/// Knots needed to raise every interior breakpoint to multiplicity `degree`
+fn bezier_insertions(knots: &[f64], degree: usize) -> Vec<f64> {
+    let values = breakpoints(knots, degree);
+
+    values[1..values.len() - 1]
+        .iter()
+        .flat_map(|&t| {
+            let s = knot_multiplicity(knots, t);
+            std::iter::repeat_n(t, degree.saturating_sub(s))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::{Array2, Array3};
+
+    fn create_patchwork() -> NURBSSurface {
+        let u_res = 6;
+        let v_res = 5;
+
+        let mut control_points = Array3::zeros((u_res, v_res, 3));
+        let mut weights = Array2::ones((u_res, v_res));
+        for i in 0..u_res {
+            for j in 0..v_res {
+                control_points[[i, j, 0]] = i as f64;
+                control_points[[i, j, 1]] = j as f64 + 0.3 * (i as f64).cos();
+                control_points[[i, j, 2]] = (i as f64 * 0.9).sin() * (j as f64 * 0.4).cos();
+                weights[[i, j]] = 1.0 + 0.3 * ((i * j) % 3) as f64;
+            }
+        }
+
+        // Double interior knot in u, two simple interior knots in v
+        let knots_u = vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 2.0, 2.0, 2.0, 2.0];
+        let knots_v = vec![0.0, 0.0, 0.0, 0.2, 0.6, 1.0, 1.0, 1.0];
+
+        NURBSSurface::new(3, 2, control_points, weights, knots_u, knots_v)
+    }
+
+    #[test]
+    fn test_patch_grid_layout() {
+        let surface = create_patchwork();
+        let patches = surface.to_bezier_patches();
+
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].len(), 3);
+        assert_eq!(patches[0][0].u_range, [0.0, 0.5]);
+        assert_eq!(patches[1][2].u_range, [0.5, 2.0]);
+        assert_eq!(patches[1][2].v_range, [0.6, 1.0]);
+
+        for patch in patches.iter().flatten() {
+            assert_eq!(patch.surface.dimensions(), (4, 3));
+        }
+    }
+
+    #[test]
+    fn test_patches_reproduce_surface() {
+        let surface = create_patchwork();
+
+        for patch in surface.to_bezier_patches().iter().flatten() {
+            let [u0, u1] = patch.u_range;
+            let [v0, v1] = patch.v_range;
+
+            for a in 0..=4 {
+                for b in 0..=4 {
+                    let u = u0 + (u1 - u0) * a as f64 / 4.0;
+                    let v = v0 + (v1 - v0) * b as f64 / 4.0;
+
+                    let expected = surface.evaluate(u, v);
+                    let actual = patch.surface.evaluate(u, v);
+                    for k in 0..3 {
+                        assert_relative_eq!(expected[k], actual[k], epsilon = 1e-10);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Max deviation between a patch and `surface` over the patch rectangle
+    fn patch_deviation(patch: &BezierPatch, surface: &NURBSSurface) -> f64 {
+        let [u0, u1] = patch.u_range;
+        let [v0, v1] = patch.v_range;
+        let mut deviation: f64 = 0.0;
+        for a in 0..=4 {
+            for b in 0..=4 {
+                let u = u0 + (u1 - u0) * a as f64 / 4.0;
+                let v = v0 + (v1 - v0) * b as f64 / 4.0;
+                let (expected, actual) = (surface.evaluate(u, v), patch.surface.evaluate(u, v));
+                for k in 0..3 {
+                    deviation = deviation.max((expected[k] - actual[k]).abs());
+                }
+            }
+        }
+        deviation
+    }
+
+    #[test]
+    fn test_interior_knot_of_full_multiplicity() {
+        // Two cubic halves joined by a knot of multiplicity p + 1 at u = 0.5,
+        // with the right half lifted so the surface jumps there
+        let mut control_points = Array3::zeros((8, 5, 3));
+        let mut weights = Array2::ones((8, 5));
+        for i in 0..8 {
+            for j in 0..5 {
+                control_points[[i, j, 0]] = i as f64;
+                control_points[[i, j, 1]] = j as f64 + 0.3 * (i as f64).cos();
+                control_points[[i, j, 2]] = (i as f64 * 0.9).sin() + if i < 4 { 0.0 } else { 2.0 };
+                weights[[i, j]] = 1.0 + 0.3 * ((i * j) % 3) as f64;
+            }
+        }
+        let knots_u = vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0];
+        let knots_v = vec![0.0, 0.0, 0.0, 0.2, 0.6, 1.0, 1.0, 1.0];
+        let surface = NURBSSurface::new(3, 2, control_points.clone(), weights.clone(), knots_u, knots_v.clone());
+
+        // Each side of the jump on its own
+        let half = |rows: std::ops::Range<usize>, knots_u: Vec<f64>| {
+            NURBSSurface::new(
+                3,
+                2,
+                control_points.slice(s![rows.clone(), .., ..]).to_owned(),
+                weights.slice(s![rows, ..]).to_owned(),
+                knots_u,
+                knots_v.clone(),
+            )
+        };
+        let left = half(0..4, vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5]);
+        let right = half(4..8, vec![0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0]);
+        assert!((left.evaluate(0.5, 0.3)[2] - right.evaluate(0.5, 0.3)[2]).abs() > 0.5);
+
+        let patches = surface.to_bezier_patches();
+        assert_eq!(patches.len(), 2);
+        for (row, side) in patches.iter().zip([&left, &right]) {
+            assert_eq!(row.len(), 3);
+            for patch in row {
+                assert!(patch_deviation(patch, side) < 1e-10);
+            }
+        }
+        assert_eq!(patches[0][0].u_range, [0.0, 0.5]);
+        assert_eq!(patches[1][0].u_range, [0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_unclamped_knots() {
+        let patchwork = create_patchwork();
+
+        // Uniform knots in both directions
+        let knots_u: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let knots_v: Vec<f64> = (0..8).map(|i| i as f64).collect();
+        let surface = NURBSSurface::new(3, 2, patchwork.control_points, patchwork.weights, knots_u, knots_v);
+
+        let patches = surface.to_bezier_patches();
+        assert_eq!(patches.len(), 3);
+        assert_eq!(patches[0].len(), 3);
+        for patch in patches.iter().flatten() {
+            assert!(patch_deviation(patch, &surface) < 1e-10);
+        }
+    }
+}
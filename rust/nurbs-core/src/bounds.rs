@@ -0,0 +1,430 @@
+//! Bounding boxes and a bounding-volume hierarchy over surfaces
+//!
+//! With positive weights a NURBS surface lies in the convex hull of its
+//! control points, so the control-point box is a conservative bound. The
+//! hull of a Bezier patch follows its span much more closely, which is why
+//! the hierarchy is built over the Bezier patches of every surface rather
+//! than over whole surfaces.
+
+use crate::bezier::BezierPatch;
+use crate::projection::ClosestPoint;
+use crate::surface::NURBSSurface;
+
+/// Leaf size of the hierarchy
+const LEAF_ITEMS: usize = 4;
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl Aabb {
+    /// Box containing nothing; the identity of `union`
+    pub fn empty() -> Self {
+        Self {
+            min: [f64::INFINITY; 3],
+            max: [f64::NEG_INFINITY; 3],
+        }
+    }
+
+    /// Smallest box containing the points
+    pub fn from_points<I: IntoIterator<Item = [f64; 3]>>(points: I) -> Self {
+        points.into_iter().fold(Self::empty(), |aabb, p| aabb.union(&Self { min: p, max: p }))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|d| self.min[d] > self.max[d])
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: [0, 1, 2].map(|d| self.min[d].min(other.min[d])),
+            max: [0, 1, 2].map(|d| self.max[d].max(other.max[d])),
+        }
+    }
+
+    /// Box grown by `margin` on every side
+    pub fn expand(&self, margin: f64) -> Aabb {
+        Aabb {
+            min: self.min.map(|c| c - margin),
+            max: self.max.map(|c| c + margin),
+        }
+    }
+
+    pub fn center(&self) -> [f64; 3] {
+        [0, 1, 2].map(|d| 0.5 * (self.min[d] + self.max[d]))
+    }
+
+    /// Edge lengths
+    pub fn size(&self) -> [f64; 3] {
+        [0, 1, 2].map(|d| self.max[d] - self.min[d])
+    }
+
+    pub fn contains(&self, p: [f64; 3]) -> bool {
+        (0..3).all(|d| self.min[d] <= p[d] && p[d] <= self.max[d])
+    }
+
+    /// Whether the boxes overlap (touching counts)
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|d| self.min[d] <= other.max[d] && other.min[d] <= self.max[d])
+    }
+
+    /// Squared distance from `p` to the box (0 inside)
+    pub fn distance_squared(&self, p: [f64; 3]) -> f64 {
+        (0..3)
+            .map(|d| {
+                let excess = (self.min[d] - p[d]).max(p[d] - self.max[d]).max(0.0);
+                excess * excess
+            })
+            .sum()
+    }
+
+    /// Parameter interval [t_enter, t_exit] of the ray origin + t direction
+    /// (t >= 0) inside the box, by the slab method
+    pub fn ray_interval(&self, origin: [f64; 3], direction: [f64; 3]) -> Option<[f64; 2]> {
+        let (mut t0, mut t1) = (0.0f64, f64::INFINITY);
+        for d in 0..3 {
+            if direction[d] == 0.0 {
+                if origin[d] < self.min[d] || origin[d] > self.max[d] {
+                    return None;
+                }
+                continue;
+            }
+
+            let (a, b) = ((self.min[d] - origin[d]) / direction[d], (self.max[d] - origin[d]) / direction[d]);
+            t0 = t0.max(a.min(b));
+            t1 = t1.min(a.max(b));
+            if t0 > t1 {
+                return None;
+            }
+        }
+        Some([t0, t1])
+    }
+}
+
+impl NURBSSurface {
+    /// Box of the control points, which contains the whole surface
+    pub fn bounds(&self) -> Aabb {
+        let (u_res, v_res) = self.dimensions();
+        Aabb::from_points((0..u_res).flat_map(|i| (0..v_res).map(move |j| self.control_point(i, j))))
+    }
+}
+
+/// Bezier patch stored in a `Bvh`
+#[derive(Debug, Clone)]
+pub struct BvhItem {
+    /// Index of the surface the patch was taken from
+    pub surface: usize,
+    pub patch: BezierPatch,
+    pub bounds: Aabb,
+}
+
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounds: Aabb,
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone)]
+enum NodeKind {
+    /// Items `start..end`
+    Leaf { start: usize, end: usize },
+    Inner { left: usize, right: usize },
+}
+
+/// Bounding-volume hierarchy over the Bezier patches of a set of surfaces
+///
+/// Nodes split at the median patch center along the longest axis. Queries
+/// return indices into `items()`.
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    items: Vec<BvhItem>,
+}
+
+impl Bvh {
+    /// Build the hierarchy over all patches of `surfaces`
+    pub fn new(surfaces: &[NURBSSurface]) -> Bvh {
+        let mut items: Vec<BvhItem> = surfaces
+            .iter()
+            .enumerate()
+            .flat_map(|(surface, s)| {
+                s.to_bezier_patches().into_iter().flatten().map(move |patch| BvhItem {
+                    surface,
+                    bounds: patch.surface.bounds(),
+                    patch,
+                })
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            let len = items.len();
+            build(&mut nodes, &mut items, 0, len);
+        }
+        Bvh { nodes, items }
+    }
+
+    pub fn items(&self) -> &[BvhItem] {
+        &self.items
+    }
+
+    /// Box of everything in the hierarchy
+    pub fn bounds(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::empty(), |node| node.bounds)
+    }
+
+    /// Items whose boxes overlap `region`
+    pub fn query(&self, region: &Aabb) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.visit(|bounds| bounds.intersects(region), |index| found.push(index));
+        found
+    }
+
+    /// Items whose boxes the ray origin + t direction (t >= 0) passes
+    /// through, as (item, t_enter) ordered by t_enter
+    pub fn ray_candidates(&self, origin: [f64; 3], direction: [f64; 3]) -> Vec<(usize, f64)> {
+        let mut found = Vec::new();
+        self.visit(
+            |bounds| bounds.ray_interval(origin, direction).is_some(),
+            |index| {
+                if let Some([t, _]) = self.items[index].bounds.ray_interval(origin, direction) {
+                    found.push((index, t));
+                }
+            },
+        );
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        found
+    }
+
+    /// Closest point on any surface, as (surface index, point)
+    ///
+    /// Patches are projected nearest box first and skipped once their box is
+    /// farther away than the best point found.
+    pub fn closest_point(&self, p: [f64; 3]) -> Option<(usize, ClosestPoint)> {
+        let mut best: Option<(usize, ClosestPoint)> = None;
+        if !self.nodes.is_empty() {
+            self.closest_in(0, p, &mut best);
+        }
+        best
+    }
+
+    /// Pairs (i, j) of items of `self` and `other` whose boxes, grown by
+    /// `margin`, overlap
+    pub fn overlaps(&self, other: &Bvh, margin: f64) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        if !self.nodes.is_empty() && !other.nodes.is_empty() {
+            self.overlaps_in(other, 0, 0, margin, &mut pairs);
+        }
+        pairs
+    }
+
+    /// Pairs (i, j), i < j, of items from different surfaces whose boxes,
+    /// grown by `margin`, overlap: candidates for clashes within the model
+    pub fn clashes(&self, margin: f64) -> Vec<(usize, usize)> {
+        self.overlaps(self, margin)
+            .into_iter()
+            .filter(|&(i, j)| i < j && self.items[i].surface != self.items[j].surface)
+            .collect()
+    }
+
+    /// Depth-first traversal into nodes accepted by `enter`, reporting the
+    /// items of accepted leaves
+    fn visit(&self, enter: impl Fn(&Aabb) -> bool, mut report: impl FnMut(usize)) {
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !enter(&node.bounds) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { start, end } => {
+                    (start..end).filter(|&i| enter(&self.items[i].bounds)).for_each(&mut report)
+                }
+                NodeKind::Inner { left, right } => stack.extend([right, left]),
+            }
+        }
+    }
+
+    fn closest_in(&self, index: usize, p: [f64; 3], best: &mut Option<(usize, ClosestPoint)>) {
+        let node = &self.nodes[index];
+        let bound = |best: &Option<(usize, ClosestPoint)>| best.as_ref().map_or(f64::INFINITY, |b| b.1.distance);
+        if node.bounds.distance_squared(p) > bound(best).powi(2) {
+            return;
+        }
+
+        match node.kind {
+            NodeKind::Leaf { start, end } => {
+                for item in &self.items[start..end] {
+                    if item.bounds.distance_squared(p) > bound(best).powi(2) {
+                        continue;
+                    }
+                    let candidate = item.patch.surface.closest_point(p);
+                    if candidate.distance < bound(best) {
+                        *best = Some((item.surface, candidate));
+                    }
+                }
+            }
+            NodeKind::Inner { left, right } => {
+                let (near, far) = if self.nodes[left].bounds.distance_squared(p)
+                    <= self.nodes[right].bounds.distance_squared(p)
+                {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+                self.closest_in(near, p, best);
+                self.closest_in(far, p, best);
+            }
+        }
+    }
+
+    fn overlaps_in(&self, other: &Bvh, a: usize, b: usize, margin: f64, pairs: &mut Vec<(usize, usize)>) {
+        let (node_a, node_b) = (&self.nodes[a], &other.nodes[b]);
+        if !node_a.bounds.expand(margin).intersects(&node_b.bounds) {
+            return;
+        }
+
+        match (&node_a.kind, &node_b.kind) {
+            (&NodeKind::Leaf { start, end }, &NodeKind::Leaf { start: s, end: e }) => {
+                for i in start..end {
+                    let grown = self.items[i].bounds.expand(margin);
+                    pairs.extend((s..e).filter(|&j| grown.intersects(&other.items[j].bounds)).map(|j| (i, j)));
+                }
+            }
+            // Descend into the inner node, or the larger one if both are
+            (&NodeKind::Inner { left, right }, &NodeKind::Leaf { .. }) => {
+                self.overlaps_in(other, left, b, margin, pairs);
+                self.overlaps_in(other, right, b, margin, pairs);
+            }
+            (&NodeKind::Inner { left, right }, &NodeKind::Inner { .. })
+                if volume(&node_a.bounds) >= volume(&node_b.bounds) =>
+            {
+                self.overlaps_in(other, left, b, margin, pairs);
+                self.overlaps_in(other, right, b, margin, pairs);
+            }
+            (_, &NodeKind::Inner { left, right }) => {
+                self.overlaps_in(other, a, left, margin, pairs);
+                self.overlaps_in(other, a, right, margin, pairs);
+            }
+        }
+    }
+}
+
+/// Build the subtree over `items[start..end]` and return its node index
+fn build(nodes: &mut Vec<BvhNode>, items: &mut [BvhItem], start: usize, end: usize) -> usize {
+    let bounds = items[start..end].iter().fold(Aabb::empty(), |aabb, item| aabb.union(&item.bounds));
+    let index = nodes.len();
+    nodes.push(BvhNode {
+        bounds,
+        kind: NodeKind::Leaf { start, end },
+    });
+    if end - start <= LEAF_ITEMS {
+        return index;
+    }
+
+    let size = bounds.size();
+    let axis = (0..3).max_by(|&a, &b| size[a].total_cmp(&size[b])).unwrap();
+    let mid = (start + end) / 2;
+    items[start..end].select_nth_unstable_by(mid - start, |a, b| {
+        a.bounds.center()[axis].total_cmp(&b.bounds.center()[axis])
+    });
+
+    let left = build(nodes, items, start, mid);
+    let right = build(nodes, items, mid, end);
+    nodes[index].kind = NodeKind::Inner { left, right };
+    index
+}
+
+fn volume(aabb: &Aabb) -> f64 {
+    aabb.size().iter().product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn create_spheres() -> Vec<NURBSSurface> {
+        vec![
+            NURBSSurface::sphere([0.0, 0.0, 0.0], 1.0),
+            NURBSSurface::sphere([3.0, 0.0, 0.0], 1.0),
+            NURBSSurface::torus([0.0, 5.0, 0.0], [0.0, 0.0, 1.0], 2.0, 0.5),
+        ]
+    }
+
+    #[test]
+    fn test_aabb_operations() {
+        let aabb = Aabb::from_points([[0.0, 1.0, 2.0], [1.0, -1.0, 3.0]]);
+        assert_eq!(aabb.min, [0.0, -1.0, 2.0]);
+        assert_eq!(aabb.max, [1.0, 1.0, 3.0]);
+        assert!(Aabb::empty().is_empty());
+        assert!(aabb.contains([0.5, 0.0, 2.5]));
+        assert_relative_eq!(aabb.distance_squared([2.0, 0.0, 5.0]), 5.0);
+
+        assert_eq!(aabb.ray_interval([-1.0, 0.0, 2.5], [1.0, 0.0, 0.0]), Some([1.0, 2.0]));
+        assert_eq!(aabb.ray_interval([-1.0, 0.0, 2.5], [-1.0, 0.0, 0.0]), None);
+        assert_eq!(aabb.ray_interval([-1.0, 5.0, 2.5], [1.0, 0.0, 0.0]), None);
+
+        // Surface samples stay inside the control box and the patch boxes
+        let sphere = NURBSSurface::sphere([1.0, 2.0, 3.0], 2.0);
+        let bounds = sphere.bounds();
+        assert_relative_eq!(bounds.max[2], 5.0, epsilon = 1e-12);
+        let patches: Vec<BezierPatch> = sphere.to_bezier_patches().into_iter().flatten().collect();
+        for i in 0..=16 {
+            for j in 0..=16 {
+                let (u, v) = (i as f64 / 16.0, j as f64 / 16.0);
+                let p = sphere.evaluate(u, v);
+                assert!(bounds.expand(1e-12).contains(p));
+                assert!(patches.iter().any(|patch| patch.surface.bounds().expand(1e-12).contains(p)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let bvh = Bvh::new(&create_spheres());
+        assert_eq!(bvh.items().len(), 8 + 8 + 16);
+
+        let region = Aabb::from_points([[0.5, -0.2, -0.2], [2.5, 0.2, 0.2]]);
+        let mut found = bvh.query(&region);
+        found.sort();
+        let expected: Vec<usize> = (0..bvh.items().len())
+            .filter(|&i| bvh.items()[i].bounds.intersects(&region))
+            .collect();
+        assert_eq!(found, expected);
+        assert!(found.iter().any(|&i| bvh.items()[i].surface == 0));
+        assert!(found.iter().any(|&i| bvh.items()[i].surface == 1));
+
+        // A ray along the x axis meets both spheres, the first one first
+        let hits = bvh.ray_candidates([-5.0, 0.1, 0.1], [1.0, 0.0, 0.0]);
+        assert_eq!(bvh.items()[hits[0].0].surface, 0);
+        assert!(hits.iter().all(|&(i, _)| bvh.items()[i].surface != 2));
+        assert!(hits.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn test_closest_point_and_clashes() {
+        let bvh = Bvh::new(&create_spheres());
+
+        let (surface, closest) = bvh.closest_point([4.5, 0.0, 0.3]).unwrap();
+        assert_eq!(surface, 1);
+        assert_relative_eq!(closest.distance, (1.5f64.powi(2) + 0.09).sqrt() - 1.0, epsilon = 1e-8);
+
+        let (surface, closest) = bvh.closest_point([0.0, 7.9, 0.0]).unwrap();
+        assert_eq!(surface, 2);
+        assert_relative_eq!(closest.distance, 0.4, epsilon = 1e-8);
+        assert!(bvh.closest_point([f64::NAN, 0.0, 0.0]).is_none());
+
+        // The spheres are 1 apart and 1.5 from the torus
+        assert!(bvh.clashes(0.0).is_empty());
+        let clashes = bvh.clashes(1.2);
+        assert!(!clashes.is_empty());
+        assert!(clashes.iter().all(|&(i, j)| {
+            let pair = [bvh.items()[i].surface, bvh.items()[j].surface];
+            pair == [0, 1] || pair == [1, 0]
+        }));
+    }
+}
@@ -0,0 +1,629 @@
+//! Surface constructors from curves
+//!
+//! Extrusion, revolution, ruled and translational sweep surfaces are exact
+//! tensor products of the input curves (NURBS Book, chapter 8). Lofting
+//! interpolates compatible section curves across their homogeneous control
+//! points (section 10.3); the rotation-minimizing sweep lofts rigidly moved
+//! copies of the profile and is therefore an approximation.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use ndarray::{Array1, Array2, Array3, Axis};
+
+use crate::curve::NURBSCurve;
+use crate::error::NurbsError;
+use crate::fitting::{averaged_knots, collocation_matrix, grid_parameters, Parameterization};
+use crate::refine::{knot_multiplicity, refine_knots};
+use crate::surface::{remap_knots, NURBSSurface};
+use crate::vector::{add, cross, dot, normalize, perpendicular, scale, sub};
+
+/// Distance on the normalized domain [0, 1] below which knots of different
+/// curves are merged in `make_compatible`
+const KNOT_TOLERANCE: f64 = 1e-10;
+
+/// How the profile is carried along the path in `sweep`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepMode {
+    /// Profile is translated without rotating (exact)
+    Translational,
+    /// Profile follows a rotation-minimizing frame; `sections` copies of
+    /// it are placed along the path and lofted with a cubic in v
+    RotationMinimizing { sections: usize },
+}
+
+impl NURBSCurve {
+    /// Exact circular arc as a rational quadratic curve (NURBS Book, A7.1)
+    ///
+    /// The arc runs from `start` to `end` (radians, measured from `x_axis`
+    /// towards `y_axis`, which must be orthonormal) and is defined on [0, 1].
+    /// A sweep of 2π gives a closed circle.
+    pub fn circular_arc(
+        center: [f64; 3],
+        x_axis: [f64; 3],
+        y_axis: [f64; 3],
+        radius: f64,
+        start: f64,
+        end: f64,
+    ) -> NURBSCurve {
+        let (coefficients, weights, knots) = unit_arc(start, end - start);
+
+        let mut control_points = Array2::zeros((coefficients.len(), 3));
+        for (i, &[a, b]) in coefficients.iter().enumerate() {
+            let point = add(&center, &add(&scale(&x_axis, radius * a), &scale(&y_axis, radius * b)));
+            for d in 0..3 {
+                control_points[[i, d]] = point[d];
+            }
+        }
+
+        NURBSCurve::new(2, control_points, Array1::from(weights), knots)
+    }
+}
+
+/// Surface swept by moving `curve` along `direction`
+///
+/// S(u, v) = C(u) + v * direction for v in [0, 1].
+pub fn extrude(curve: &NURBSCurve, direction: [f64; 3]) -> Result<NURBSSurface, NurbsError> {
+    check_space_curve(curve)?;
+
+    let n = curve.num_control_points();
+    let mut control_points = Array3::zeros((n, 2, 3));
+    let mut weights = Array2::zeros((n, 2));
+    for i in 0..n {
+        for d in 0..3 {
+            control_points[[i, 0, d]] = curve.control_points[[i, d]];
+            control_points[[i, 1, d]] = curve.control_points[[i, d]] + direction[d];
+        }
+        weights[[i, 0]] = curve.weights[i];
+        weights[[i, 1]] = curve.weights[i];
+    }
+
+    NURBSSurface::try_new(
+        curve.degree,
+        1,
+        control_points,
+        weights,
+        curve.knots.clone(),
+        vec![0.0, 0.0, 1.0, 1.0],
+    )
+}
+
+/// Surface of revolution of `profile` about an axis (NURBS Book, A8.1)
+///
+/// The profile is rotated by `angle` radians (up to 2π) about the line
+/// through `axis_point` along `axis_direction`. u runs around the axis on
+/// [0, 1] as rational quadratic arcs; v follows the profile.
+pub fn revolve(
+    profile: &NURBSCurve,
+    axis_point: [f64; 3],
+    axis_direction: [f64; 3],
+    angle: f64,
+) -> Result<NURBSSurface, NurbsError> {
+    check_space_curve(profile)?;
+    let axis = normalize(&axis_direction).ok_or(NurbsError::ZeroVector { field: "axis direction" })?;
+
+    let (coefficients, arc_weights, knots_u) = unit_arc(0.0, angle.clamp(-TAU, TAU));
+    let n_u = coefficients.len();
+    let n_v = profile.num_control_points();
+
+    let mut control_points = Array3::zeros((n_u, n_v, 3));
+    let mut weights = Array2::zeros((n_u, n_v));
+    for j in 0..n_v {
+        let point = row_point(&profile.control_points, j);
+
+        // Circle of the control point: center on the axis, in-plane frame
+        let center = add(&axis_point, &scale(&axis, dot(&sub(&point, &axis_point), &axis)));
+        let offset = sub(&point, &center);
+        let radius = dot(&offset, &offset).sqrt();
+        let x = normalize(&offset).unwrap_or([0.0; 3]);
+        let y = cross(&axis, &x);
+
+        for (i, &[a, b]) in coefficients.iter().enumerate() {
+            let p = add(&center, &add(&scale(&x, radius * a), &scale(&y, radius * b)));
+            for d in 0..3 {
+                control_points[[i, j, d]] = p[d];
+            }
+            weights[[i, j]] = arc_weights[i] * profile.weights[j];
+        }
+    }
+
+    NURBSSurface::try_new(2, profile.degree, control_points, weights, knots_u, profile.knots.clone())
+}
+
+/// Ruled surface S(u, v) = (1 - v) a(u) + v b(u)
+///
+/// Both curves are brought to a common degree, the parameter domain
+/// [0, 1] and a merged knot vector first.
+pub fn ruled(a: &NURBSCurve, b: &NURBSCurve) -> Result<NURBSSurface, NurbsError> {
+    skin(&make_compatible(&[a.clone(), b.clone()])?, 1)
+}
+
+/// Lofted (skinned) surface through a sequence of section curves
+///
+/// Sections are made compatible as in `ruled`; u follows the sections and
+/// v interpolates across them with the given degree, at chord-length
+/// parameters averaged over the control points (NURBS Book, eq. 10.8).
+pub fn loft(sections: &[NURBSCurve], degree_v: usize) -> Result<NURBSSurface, NurbsError> {
+    if degree_v == 0 {
+        return Err(NurbsError::ZeroDegree { axis: "v" });
+    }
+    if sections.len() < degree_v + 1 {
+        return Err(NurbsError::TooFewPoints {
+            axis: "v",
+            degree: degree_v,
+            points: sections.len(),
+        });
+    }
+
+    skin(&make_compatible(sections)?, degree_v)
+}
+
+/// Sweep `profile` along `path`
+///
+/// The profile is given in place at the start of the path. u follows the
+/// profile and v the path. Translational sweeps keep the path's knots;
+/// rotation-minimizing sweeps are lofts on [0, 1] in v and need at least
+/// two sections.
+pub fn sweep(profile: &NURBSCurve, path: &NURBSCurve, mode: SweepMode) -> Result<NURBSSurface, NurbsError> {
+    check_space_curve(profile)?;
+    check_space_curve(path)?;
+
+    match mode {
+        SweepMode::Translational => {
+            // Exact tensor product: P_ij = C_i + T_j - T_0, w_ij = w_i w_j
+            let (n_u, n_v) = (profile.num_control_points(), path.num_control_points());
+            let origin = path.evaluate(path.domain()[0]);
+
+            let mut control_points = Array3::zeros((n_u, n_v, 3));
+            let mut weights = Array2::zeros((n_u, n_v));
+            for i in 0..n_u {
+                for j in 0..n_v {
+                    for d in 0..3 {
+                        control_points[[i, j, d]] =
+                            profile.control_points[[i, d]] + path.control_points[[j, d]] - origin[d];
+                    }
+                    weights[[i, j]] = profile.weights[i] * path.weights[j];
+                }
+            }
+
+            NURBSSurface::try_new(
+                profile.degree,
+                path.degree,
+                control_points,
+                weights,
+                profile.knots.clone(),
+                path.knots.clone(),
+            )
+        }
+        SweepMode::RotationMinimizing { sections } => {
+            if sections < 2 {
+                return Err(NurbsError::TooFewPoints { axis: "v", degree: 1, points: sections });
+            }
+
+            let [t0, t1] = path.domain();
+            let stations: Vec<f64> = (0..sections)
+                .map(|k| t0 + (t1 - t0) * k as f64 / (sections - 1) as f64)
+                .collect();
+            let frames = rotation_minimizing_frames(path, &stations);
+
+            let (origin, base) = frames[0];
+            let copies: Vec<NURBSCurve> = frames
+                .iter()
+                .map(|(position, frame)| {
+                    let mut copy = profile.clone();
+                    for i in 0..copy.num_control_points() {
+                        let local = sub(&row_point(&profile.control_points, i), &origin);
+
+                        // Coordinates in the start frame, re-expressed in this one
+                        let coords = base.map(|axis| dot(&local, &axis));
+                        let mut moved = *position;
+                        for (c, axis) in coords.iter().zip(frame) {
+                            moved = add(&moved, &scale(axis, *c));
+                        }
+                        for (d, value) in moved.into_iter().enumerate() {
+                            copy.control_points[[i, d]] = value;
+                        }
+                    }
+                    copy
+                })
+                .collect();
+
+            loft(&copies, 3.min(sections - 1))
+        }
+    }
+}
+
+/// Interpolate compatible curves across their homogeneous control points
+fn skin(curves: &[NURBSCurve], degree_v: usize) -> Result<NURBSSurface, NurbsError> {
+    let count = curves.len();
+    let n = curves[0].num_control_points();
+
+    // v parameters from the Euclidean control points of all sections
+    let mut points = Array3::zeros((n, count, 3));
+    let mut rhs = Array2::zeros((count, n * 4));
+    for (k, curve) in curves.iter().enumerate() {
+        let pw = curve.homogeneous();
+        for i in 0..n {
+            for d in 0..3 {
+                points[[i, k, d]] = curve.control_points[[i, d]];
+            }
+            for d in 0..4 {
+                rhs[[k, i * 4 + d]] = pw[[i, d]];
+            }
+        }
+    }
+
+    let params = grid_parameters(&points, Axis(1), Parameterization::ChordLength)?;
+    let knots_v = averaged_knots(&params, degree_v);
+    collocation_matrix(&params, &knots_v, degree_v)
+        .solve(&mut rhs)
+        .ok_or(NurbsError::SingularSystem)?;
+
+    let mut pw = Array3::zeros((n, count, 4));
+    for k in 0..count {
+        for i in 0..n {
+            for d in 0..4 {
+                pw[[i, k, d]] = rhs[[k, i * 4 + d]];
+            }
+        }
+    }
+
+    // Interpolated weights can turn negative for wildly varying sections
+    NURBSSurface::try_from_homogeneous(curves[0].degree, degree_v, &pw, curves[0].knots.clone(), knots_v)
+}
+
+/// Common degree, clamped domain [0, 1] and knot vector for a set of curves
+///
+/// Knots that differ by less than `KNOT_TOLERANCE` after mapping to [0, 1]
+/// are merged into one.
+fn make_compatible(curves: &[NURBSCurve]) -> Result<Vec<NURBSCurve>, NurbsError> {
+    for curve in curves {
+        check_space_curve(curve)?;
+    }
+
+    let degree = curves.iter().map(|c| c.degree).max().unwrap_or(0);
+    let mut normalized: Vec<NURBSCurve> = curves
+        .iter()
+        .map(|curve| {
+            let mut curve = curve.clamped().elevate_degree(degree - curve.degree);
+            curve.knots = remap_knots(&curve.knots, curve.domain(), [0.0, 1.0]);
+            curve
+        })
+        .collect();
+
+    // Distinct knot values, each standing for the knots within tolerance
+    let mut values: Vec<f64> = normalized.iter().flat_map(|c| c.knots.iter().copied()).collect();
+    values.sort_by(f64::total_cmp);
+    values.dedup_by(|next, kept| *next - *kept <= KNOT_TOLERANCE);
+    if let Some(end) = values.last_mut() {
+        *end = 1.0;
+    }
+    for curve in &mut normalized {
+        for knot in &mut curve.knots {
+            if let Some(&value) = values.iter().find(|&&v| (*knot - v).abs() <= KNOT_TOLERANCE) {
+                *knot = value;
+            }
+        }
+    }
+
+    // Union of the interior knots with their highest multiplicity
+    let mut merged: Vec<(f64, usize)> = Vec::new();
+    for curve in &normalized {
+        let interior = &curve.knots[degree + 1..curve.knots.len() - degree - 1];
+        for &t in interior {
+            let s = knot_multiplicity(interior, t);
+            match merged.iter_mut().find(|(value, _)| *value == t) {
+                Some(entry) => entry.1 = entry.1.max(s),
+                None => merged.push((t, s)),
+            }
+        }
+    }
+
+    Ok(normalized
+        .into_iter()
+        .map(|curve| {
+            let missing: Vec<f64> = merged
+                .iter()
+                .flat_map(|&(t, s)| std::iter::repeat_n(t, s - knot_multiplicity(&curve.knots, t)))
+                .collect();
+            let (knots, qw) = refine_knots(&curve.knots, degree, &curve.homogeneous(), &missing);
+            NURBSCurve::from_homogeneous(degree, &qw, knots)
+        })
+        .collect())
+}
+
+/// Positions and frames (normal, binormal, tangent) along a path by the
+/// double reflection method (Wang et al., 2008)
+fn rotation_minimizing_frames(path: &NURBSCurve, stations: &[f64]) -> Vec<([f64; 3], [[f64; 3]; 3])> {
+    let samples: Vec<([f64; 3], [f64; 3])> = stations
+        .iter()
+        .map(|&t| {
+            let ders = path.derivatives(t, 1);
+            let position = [ders[0][0], ders[0][1], ders[0][2]];
+            let tangent = normalize(&[ders[1][0], ders[1][1], ders[1][2]]).unwrap_or([0.0, 0.0, 1.0]);
+            (position, tangent)
+        })
+        .collect();
+
+    let t0 = samples[0].1;
+    let mut r = perpendicular(&t0);
+
+    let mut frames = Vec::with_capacity(samples.len());
+    frames.push((samples[0].0, [r, cross(&t0, &r), t0]));
+
+    for window in samples.windows(2) {
+        let ((x0, t0), (x1, t1)) = (window[0], window[1]);
+
+        let v1 = sub(&x1, &x0);
+        let c1 = dot(&v1, &v1);
+        let (r_l, t_l) = if c1 > 0.0 {
+            (
+                sub(&r, &scale(&v1, 2.0 * dot(&v1, &r) / c1)),
+                sub(&t0, &scale(&v1, 2.0 * dot(&v1, &t0) / c1)),
+            )
+        } else {
+            (r, t0)
+        };
+
+        let v2 = sub(&t1, &t_l);
+        let c2 = dot(&v2, &v2);
+        r = if c2 > 0.0 { sub(&r_l, &scale(&v2, 2.0 * dot(&v2, &r_l) / c2)) } else { r_l };
+
+        frames.push((x1, [r, cross(&t1, &r), t1]));
+    }
+
+    frames
+}
+
+/// Control point coefficients (cos, sin), weights and knots of an arc of
+/// the unit circle (NURBS Book, A7.1)
+fn unit_arc(start: f64, sweep: f64) -> (Vec<[f64; 2]>, Vec<f64>, Vec<f64>) {
+    let arcs = ((sweep.abs() / FRAC_PI_2).ceil() as usize).clamp(1, 4);
+    let step = sweep / arcs as f64;
+    let w1 = (step / 2.0).cos();
+
+    let mut coefficients = vec![[start.cos(), start.sin()]];
+    let mut weights = vec![1.0];
+    for k in 1..=arcs {
+        let mid = start + step * (k as f64 - 0.5);
+        let end = start + step * k as f64;
+        coefficients.push([mid.cos() / w1, mid.sin() / w1]);
+        coefficients.push([end.cos(), end.sin()]);
+        weights.extend([w1, 1.0]);
+    }
+
+    let mut knots = vec![0.0; 3];
+    for k in 1..arcs {
+        let t = k as f64 / arcs as f64;
+        knots.extend([t, t]);
+    }
+    knots.extend([1.0; 3]);
+
+    (coefficients, weights, knots)
+}
+
+fn check_space_curve(curve: &NURBSCurve) -> Result<(), NurbsError> {
+    match curve.dimension() {
+        3 => Ok(()),
+        found => Err(NurbsError::InvalidDimension { expected: 3, found }),
+    }
+}
+
+fn row_point(points: &Array2<f64>, i: usize) -> [f64; 3] {
+    [points[[i, 0]], points[[i, 1]], points[[i, 2]]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::distance;
+    use approx::assert_relative_eq;
+
+    fn create_wave() -> NURBSCurve {
+        let control_points = Array2::from_shape_vec(
+            (5, 3),
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 2.0, -0.5, 0.0, 3.0, 0.5, 0.0, 4.0, 0.0, 0.0],
+        )
+        .unwrap();
+        let weights = Array1::from(vec![1.0, 1.5, 0.8, 1.2, 1.0]);
+        let knots = vec![0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0];
+        NURBSCurve::new(3, control_points, weights, knots)
+    }
+
+    fn point(v: &[f64]) -> [f64; 3] {
+        [v[0], v[1], v[2]]
+    }
+
+    #[test]
+    fn test_circular_arc_is_exact() {
+        let arc = NURBSCurve::circular_arc([1.0, 2.0, 3.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], 2.5, 0.3, 4.0);
+
+        for k in 0..=20 {
+            let p = point(&arc.evaluate(k as f64 / 20.0));
+            assert_relative_eq!(distance(&p, &[1.0, 2.0, 3.0]), 2.5, epsilon = 1e-12);
+            assert_relative_eq!(p[1], 2.0, epsilon = 1e-12);
+        }
+
+        let end = arc.evaluate(1.0);
+        assert_relative_eq!(end[0], 1.0 + 2.5 * 4.0f64.cos(), epsilon = 1e-12);
+        assert_relative_eq!(end[2], 3.0 + 2.5 * 4.0f64.sin(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_extrude_and_revolve() {
+        let wave = create_wave();
+        let surface = extrude(&wave, [0.0, 0.0, 2.0]).unwrap();
+        for &(u, v) in &[(0.0, 0.0), (0.3, 0.5), (0.8, 1.0)] {
+            let c = wave.evaluate(u);
+            let s = surface.evaluate(u, v);
+            assert_relative_eq!(s[0], c[0], epsilon = 1e-12);
+            assert_relative_eq!(s[1], c[1], epsilon = 1e-12);
+            assert_relative_eq!(s[2], 2.0 * v, epsilon = 1e-12);
+        }
+
+        // Revolving about the x axis keeps each profile point's radius
+        let revolved = revolve(&wave, [0.0; 3], [1.0, 0.0, 0.0], TAU).unwrap();
+        assert_eq!(revolved.degree_u, 2);
+        for &v in &[0.0, 0.2, 0.45, 0.9] {
+            let c = wave.evaluate(v);
+            for k in 0..=12 {
+                let s = revolved.evaluate(k as f64 / 12.0, v);
+                assert_relative_eq!(s[0], c[0], epsilon = 1e-12);
+                assert_relative_eq!(s[1].hypot(s[2]), c[1].abs(), epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ruled_surface_between_incompatible_curves() {
+        let a = create_wave();
+        let b = NURBSCurve::circular_arc([2.0, 0.0, 3.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], 2.0, 0.0, 3.0);
+
+        let surface = ruled(&a, &b).unwrap();
+        assert_eq!(surface.degree_u, 3);
+        assert_eq!(surface.degree_v, 1);
+
+        for k in 0..=10 {
+            let u = k as f64 / 10.0;
+            let (sa, sb) = (surface.evaluate(u, 0.0), surface.evaluate(u, 1.0));
+            let (ca, cb) = (a.evaluate(u), b.evaluate(u));
+            for d in 0..3 {
+                assert_relative_eq!(sa[d], ca[d], epsilon = 1e-10);
+                assert_relative_eq!(sb[d], cb[d], epsilon = 1e-10);
+            }
+
+            // Rulings are straight: interior points lie on the segment
+            let mid = point(&surface.evaluate(u, 0.5));
+            let (pa, pb) = (point(&ca), point(&cb));
+            assert_relative_eq!(distance(&pa, &mid) + distance(&mid, &pb), distance(&pa, &pb), epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_ruled_surface_merges_knots_and_clamps() {
+        // Interior knots that agree up to round-off merge into one
+        let a = create_wave();
+        let mut b = create_wave();
+        b.knots[4] += 1e-13;
+        for i in 0..5 {
+            b.control_points[[i, 2]] = 1.0;
+        }
+        let surface = ruled(&a, &b).unwrap();
+        assert_eq!(surface.dimensions(), (5, 2));
+
+        // An unclamped uniform cubic is clamped to its domain [3, 5]
+        let knots: Vec<f64> = (0..9).map(|i| i as f64).collect();
+        let uniform = NURBSCurve::new(3, a.control_points.clone(), Array1::ones(5), knots);
+        let surface = ruled(&a, &uniform).unwrap();
+        for k in 0..=10 {
+            let s = surface.evaluate(k as f64 / 10.0, 1.0);
+            let c = uniform.evaluate(3.0 + 2.0 * k as f64 / 10.0);
+            for d in 0..3 {
+                assert_relative_eq!(s[d], c[d], epsilon = 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_construction_input() {
+        let wave = create_wave();
+        assert_eq!(
+            revolve(&wave, [0.0; 3], [0.0; 3], TAU).unwrap_err(),
+            NurbsError::ZeroVector { field: "axis direction" }
+        );
+        assert_eq!(
+            sweep(&wave, &wave, SweepMode::RotationMinimizing { sections: 1 }).unwrap_err(),
+            NurbsError::TooFewPoints { axis: "v", degree: 1, points: 1 }
+        );
+
+        let mut lifted = wave.clone();
+        lifted.control_points.column_mut(2).mapv_inplace(|z| z + 1.0);
+        assert_eq!(
+            loft(&[wave.clone(), lifted.clone()], 0).unwrap_err(),
+            NurbsError::ZeroDegree { axis: "v" }
+        );
+        assert_eq!(
+            loft(&[wave, lifted], 2).unwrap_err(),
+            NurbsError::TooFewPoints { axis: "v", degree: 2, points: 2 }
+        );
+    }
+
+    #[test]
+    fn test_loft_interpolates_sections() {
+        let wave = create_wave();
+        let sections: Vec<NURBSCurve> = (0..4)
+            .map(|k| {
+                let mut section = wave.clone();
+                for i in 0..section.num_control_points() {
+                    section.control_points[[i, 1]] *= 1.0 + 0.3 * k as f64;
+                    section.control_points[[i, 2]] = 1.5 * k as f64 + 0.1 * (i * k) as f64;
+                }
+                section
+            })
+            .collect();
+
+        let surface = loft(&sections, 3).unwrap();
+
+        // Sections are recovered at the averaged chord-length parameters
+        let mut points = Array3::zeros((5, 4, 3));
+        for (k, section) in sections.iter().enumerate() {
+            for i in 0..5 {
+                for d in 0..3 {
+                    points[[i, k, d]] = section.control_points[[i, d]];
+                }
+            }
+        }
+        let params = grid_parameters(&points, Axis(1), Parameterization::ChordLength).unwrap();
+
+        for (section, &v) in sections.iter().zip(&params) {
+            for k in 0..=8 {
+                let u = k as f64 / 8.0;
+                let s = surface.evaluate(u, v);
+                let c = section.evaluate(u);
+                for d in 0..3 {
+                    assert_relative_eq!(s[d], c[d], epsilon = 1e-10);
+                }
+            }
+        }
+
+        assert!(loft(&sections[..2], 3).is_err());
+    }
+
+    #[test]
+    fn test_sweeps() {
+        let profile = NURBSCurve::circular_arc([0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], 0.25, 0.0, TAU);
+
+        // Translational sweep along a straight cubic is exact
+        let line = NURBSCurve::new(
+            1,
+            Array2::from_shape_vec((2, 3), vec![0.0, 0.0, 0.0, 1.0, 0.0, 3.0]).unwrap(),
+            Array1::ones(2),
+            vec![0.0, 0.0, 1.0, 1.0],
+        );
+        let tube = sweep(&profile, &line, SweepMode::Translational).unwrap();
+        let s = tube.evaluate(0.3, 0.5);
+        let c = profile.evaluate(0.3);
+        assert_relative_eq!(s[0], c[0] + 0.5, epsilon = 1e-12);
+        assert_relative_eq!(s[2], 1.5, epsilon = 1e-12);
+
+        // Rotation-minimizing sweep along a quarter circle in the xz plane:
+        // every section stays a circle of radius 0.25 around the path
+        let path = NURBSCurve::circular_arc([2.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], 2.0, 0.0, FRAC_PI_2);
+        let bent = sweep(&profile, &path, SweepMode::RotationMinimizing { sections: 9 }).unwrap();
+
+        for &v in &[0.0, 1.0] {
+            let center = point(&path.evaluate(v));
+            for k in 0..8 {
+                let s = bent.evaluate(k as f64 / 8.0, v);
+                assert_relative_eq!(distance(&s, &center), 0.25, epsilon = 1e-10);
+            }
+        }
+
+        // The end section lies in the plane normal to the path's end tangent
+        let tangent = point(&path.derivatives(1.0, 1)[1]);
+        let end_center = point(&path.evaluate(1.0));
+        for k in 0..8 {
+            let s = bent.evaluate(k as f64 / 8.0, 1.0);
+            assert_relative_eq!(dot(&sub(&s, &end_center), &tangent), 0.0, epsilon = 1e-9);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use ndarray::{Array1, Array2};
+use rayon::prelude::*;
+
+use crate::basis::CoxDeBoor;
+use crate::error::{validate_knots, validate_weights, NurbsError};
+
+/// NURBS curve representation
+///
+/// Control points are stored row-wise, so the same type covers planar trim
+/// curves (`dim == 2`) and space curves (`dim == 3`). Knot vectors may be
+/// clamped or unclamped; the curve is defined on `[knots[p], knots[n]]`.
+#[derive(Debug, Clone)]
+pub struct NURBSCurve {
+    pub degree: usize,
+    pub control_points: Array2<f64>, // [n, dim]
+    pub weights: Array1<f64>,        // [n]
+    pub knots: Vec<f64>,
+}
+
+impl NURBSCurve {
+    /// Create new NURBS curve
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_new` for the checks.
+    pub fn new(
+        degree: usize,
+        control_points: Array2<f64>,
+        weights: Array1<f64>,
+        knots: Vec<f64>,
+    ) -> Self {
+        Self::try_new(degree, control_points, weights, knots).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create new NURBS curve, validating the inputs
+    pub fn try_new(
+        degree: usize,
+        control_points: Array2<f64>,
+        weights: Array1<f64>,
+        knots: Vec<f64>,
+    ) -> Result<Self, NurbsError> {
+        let shape = control_points.shape();
+        if shape[1] == 0 {
+            return Err(NurbsError::InvalidDimension { expected: 1, found: 0 });
+        }
+        if weights.len() != shape[0] {
+            return Err(NurbsError::WeightShapeMismatch {
+                expected: vec![shape[0]],
+                found: vec![weights.len()],
+            });
+        }
+
+        validate_knots("t", &knots, degree, shape[0])?;
+
+        if control_points.iter().any(|c| !c.is_finite()) {
+            return Err(NurbsError::NonFinite { field: "control points" });
+        }
+        validate_weights(weights.iter())?;
+
+        Ok(Self {
+            degree,
+            control_points,
+            weights,
+            knots,
+        })
+    }
+
+    /// Evaluate curve at parameter t
+    #[allow(clippy::needless_range_loop)]
+    pub fn evaluate(&self, t: f64) -> Vec<f64> {
+        let n = self.num_control_points();
+        let dim = self.dimension();
+
+        let mut basis = vec![0.0; n];
+        CoxDeBoor::evaluate_all(t, &self.knots, self.degree, &mut basis);
+
+        let mut weight_sum = 0.0;
+        let mut point = vec![0.0; dim];
+
+        for i in 0..n {
+            if basis[i] == 0.0 {
+                continue;
+            }
+
+            let w = basis[i] * self.weights[i];
+            weight_sum += w;
+
+            for k in 0..dim {
+                point[k] += w * self.control_points[[i, k]];
+            }
+        }
+
+        for value in point.iter_mut() {
+            *value /= weight_sum;
+        }
+
+        point
+    }
+
+    /// Batch evaluation (parallelized)
+    pub fn evaluate_batch(&self, params: &[f64]) -> Vec<Vec<f64>> {
+        params.par_iter().map(|&t| self.evaluate(t)).collect()
+    }
+
+    /// Rational derivatives C^(k)(t) for k = 0..=order
+    ///
+    /// Entry 0 is the curve point itself. Derivatives are computed exactly from
+    /// the homogeneous B-spline derivatives and the quotient rule.
+    #[allow(clippy::needless_range_loop)]
+    pub fn derivatives(&self, t: f64, order: usize) -> Vec<Vec<f64>> {
+        let p = self.degree;
+        let dim = self.dimension();
+        let span = CoxDeBoor::find_span(t, p, &self.knots);
+
+        let mut basis_ders = vec![vec![0.0; p + 1]; order + 1];
+        CoxDeBoor::evaluate_derivatives(t, &self.knots, p, order, &mut basis_ders);
+
+        // Derivatives of the homogeneous curve: (w * C)^(k) and w^(k)
+        let mut a_ders = vec![vec![0.0; dim]; order + 1];
+        let mut w_ders = vec![0.0; order + 1];
+
+        for k in 0..=order.min(p) {
+            for j in 0..=p {
+                let idx = span - p + j;
+                let nw = basis_ders[k][j] * self.weights[idx];
+                w_ders[k] += nw;
+
+                for d in 0..dim {
+                    a_ders[k][d] += nw * self.control_points[[idx, d]];
+                }
+            }
+        }
+
+        let mut ders = vec![vec![0.0; dim]; order + 1];
+        for k in 0..=order {
+            let mut v = a_ders[k].clone();
+
+            for i in 1..=k {
+                let c = binomial(k, i) * w_ders[i];
+                for d in 0..dim {
+                    v[d] -= c * ders[k - i][d];
+                }
+            }
+
+            for d in 0..dim {
+                ders[k][d] = v[d] / w_ders[0];
+            }
+        }
+
+        ders
+    }
+
+    /// Parameter interval [knots[p], knots[n]] on which the curve is defined
+    pub fn domain(&self) -> [f64; 2] {
+        [
+            self.knots[self.degree],
+            self.knots[self.num_control_points()],
+        ]
+    }
+
+    /// Whether both ends of the knot vector have multiplicity p + 1
+    pub fn is_clamped(&self) -> bool {
+        is_clamped(&self.knots, self.degree)
+    }
+
+    /// Homogeneous control points [w*x, w*y, ..., w]
+    pub fn homogeneous(&self) -> Array2<f64> {
+        let n = self.num_control_points();
+        let dim = self.dimension();
+        let mut pw = Array2::zeros((n, dim + 1));
+
+        for i in 0..n {
+            let w = self.weights[i];
+            for d in 0..dim {
+                pw[[i, d]] = w * self.control_points[[i, d]];
+            }
+            pw[[i, dim]] = w;
+        }
+
+        pw
+    }
+
+    /// Build a curve from homogeneous control points [n, dim + 1]
+    pub fn from_homogeneous(degree: usize, pw: &Array2<f64>, knots: Vec<f64>) -> Self {
+        let n = pw.shape()[0];
+        let dim = pw.shape()[1] - 1;
+        let mut control_points = Array2::zeros((n, dim));
+        let mut weights = Array1::zeros(n);
+
+        for i in 0..n {
+            let w = pw[[i, dim]];
+            weights[i] = w;
+            for d in 0..dim {
+                control_points[[i, d]] = pw[[i, d]] / w;
+            }
+        }
+
+        Self::new(degree, control_points, weights, knots)
+    }
+
+    /// Get control point at index i
+    pub fn control_point(&self, i: usize) -> Vec<f64> {
+        self.control_points.row(i).to_vec()
+    }
+
+    /// Get weight at index i
+    pub fn weight(&self, i: usize) -> f64 {
+        self.weights[i]
+    }
+
+    /// Number of control points
+    pub fn num_control_points(&self) -> usize {
+        self.control_points.shape()[0]
+    }
+
+    /// Spatial dimension of the control points
+    pub fn dimension(&self) -> usize {
+        self.control_points.shape()[1]
+    }
+}
+
+/// Whether both ends of a knot vector have multiplicity degree + 1
+pub(crate) fn is_clamped(knots: &[f64], degree: usize) -> bool {
+    let p = degree;
+    let m = knots.len() - 1;
+
+    (0..p).all(|i| knots[i] == knots[p]) && (0..p).all(|i| knots[m - i] == knots[m - p])
+}
+
+/// Binomial coefficient as f64 (small arguments only)
+pub(crate) fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    fn create_quarter_circle() -> NURBSCurve {
+        let control_points = array![[1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        let weights = array![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0];
+        let knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+
+        NURBSCurve::new(2, control_points, weights, knots)
+    }
+
+    #[test]
+    fn test_quarter_circle_lies_on_circle() {
+        let curve = create_quarter_circle();
+
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            let p = curve.evaluate(t);
+            assert_relative_eq!(p[0].hypot(p[1]), 1.0, epsilon = 1e-12);
+        }
+
+        let end = curve.evaluate(1.0);
+        assert_relative_eq!(end[0], 0.0, epsilon = 1e-12);
+        assert_relative_eq!(end[1], 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_derivatives_match_finite_differences() {
+        let curve = create_quarter_circle();
+        let t = 0.3;
+        let h = 1e-5;
+
+        let ders = curve.derivatives(t, 2);
+        let plus = curve.evaluate(t + h);
+        let minus = curve.evaluate(t - h);
+        let center = curve.evaluate(t);
+
+        for d in 0..2 {
+            assert_relative_eq!(ders[0][d], center[d], epsilon = 1e-12);
+            assert_relative_eq!(ders[1][d], (plus[d] - minus[d]) / (2.0 * h), epsilon = 1e-6);
+            assert_relative_eq!(
+                ders[2][d],
+                (plus[d] - 2.0 * center[d] + minus[d]) / (h * h),
+                epsilon = 1e-3
+            );
+        }
+
+        // Tangent of a circle is perpendicular to the radius
+        assert_relative_eq!(ders[0][0] * ders[1][0] + ders[0][1] * ders[1][1], 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_unclamped_curve_domain() {
+        // Uniform cubic B-spline in 3D
+        let n = 6;
+        let mut control_points = Array2::zeros((n, 3));
+        for i in 0..n {
+            control_points[[i, 0]] = i as f64;
+            control_points[[i, 2]] = 1.0;
+        }
+        let knots: Vec<f64> = (0..n + 4).map(|i| i as f64).collect();
+        let curve = NURBSCurve::new(3, control_points, Array1::ones(n), knots);
+
+        assert!(!curve.is_clamped());
+        assert_eq!(curve.domain(), [3.0, 6.0]);
+
+        // A uniform B-spline reproduces linear functions
+        let p = curve.evaluate(4.5);
+        assert_relative_eq!(p[0], 2.5, epsilon = 1e-12);
+        assert_relative_eq!(p[1], 0.0, epsilon = 1e-12);
+        assert_relative_eq!(p[2], 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_batch_evaluation() {
+        let curve = create_quarter_circle();
+
+        let params = vec![0.0, 0.5, 1.0];
+        let points = curve.evaluate_batch(&params);
+
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[1], curve.evaluate(0.5));
+    }
+}
@@ -0,0 +1,392 @@
+//! Fitting B-spline curves to ordered points
+//!
+//! Interpolation follows the NURBS Book, section 9.2.1, with end derivatives
+//! as in section 9.2.2. Approximation (section 9.4.1) interpolates the end
+//! points, places knots by eq. 9.69 and solves the normal equations for the
+//! remaining control points. Points may have any dimension, so the same
+//! routines build planar trim curves and space curves.
+
+use nalgebra::DMatrix;
+use ndarray::{Array1, Array2};
+
+use crate::basis::CoxDeBoor;
+use crate::curve::NURBSCurve;
+use crate::error::NurbsError;
+use crate::fitting::{averaged_knots, collocation_matrix, parameters, Parameterization};
+use crate::linalg::BandMatrix;
+
+/// Result of a least-squares curve fit
+#[derive(Debug, Clone)]
+pub struct CurveFit {
+    pub curve: NURBSCurve,
+    /// Largest distance from a data point to the curve at its parameter
+    pub max_error: f64,
+    /// Root mean square of those distances
+    pub rms_error: f64,
+}
+
+impl NURBSCurve {
+    /// Non-rational curve through the rows of `points` ([n, dim])
+    ///
+    /// With `end_tangents = Some((d0, dn))` the first derivative at the two
+    /// ends is prescribed as well, which adds two control points. The curve
+    /// is defined on [0, 1].
+    #[allow(clippy::needless_range_loop)]
+    pub fn interpolate(
+        points: &Array2<f64>,
+        degree: usize,
+        method: Parameterization,
+        end_tangents: Option<(&[f64], &[f64])>,
+    ) -> Result<NURBSCurve, NurbsError> {
+        let params = check_points(points, degree, end_tangents, method)?;
+        let (count, dim) = (points.shape()[0], points.shape()[1]);
+        let p = degree;
+
+        let Some((d0, dn)) = end_tangents else {
+            let knots = averaged_knots(&params, p);
+            let mut control_points = points.as_standard_layout().into_owned();
+            collocation_matrix(&params, &knots, p)
+                .solve(&mut control_points)
+                .ok_or(NurbsError::SingularSystem)?;
+            return NURBSCurve::try_new(p, control_points, Array1::ones(count), knots);
+        };
+
+        // Knots for n + 3 control points (NURBS Book, eq. 9.22)
+        let n = count - 1;
+        let mut knots = vec![0.0; p + 1];
+        for j in 0..=n + 1 - p {
+            knots.push(params[j..j + p].iter().sum::<f64>() / p as f64);
+        }
+        knots.extend(std::iter::repeat_n(1.0, p + 1));
+
+        let size = n + 3;
+        let mut matrix = BandMatrix::zeros(size, p + 1, p + 1);
+        let mut rhs = Array2::zeros((size, dim));
+
+        // Interpolate the ends and prescribe their derivatives
+        let start_scale = (knots[p + 1] - knots[0]) / p as f64;
+        let end_scale = (knots[size + p] - knots[size - 1]) / p as f64;
+        matrix.set(0, 0, 1.0);
+        matrix.set(1, 0, -1.0);
+        matrix.set(1, 1, 1.0);
+        matrix.set(size - 2, size - 2, -1.0);
+        matrix.set(size - 2, size - 1, 1.0);
+        matrix.set(size - 1, size - 1, 1.0);
+        for d in 0..dim {
+            rhs[[0, d]] = points[[0, d]];
+            rhs[[1, d]] = start_scale * d0[d];
+            rhs[[size - 2, d]] = end_scale * dn[d];
+            rhs[[size - 1, d]] = points[[n, d]];
+        }
+
+        let mut basis = vec![0.0; p + 1];
+        let (mut left, mut right) = (vec![0.0; p + 1], vec![0.0; p + 1]);
+        for k in 1..n {
+            let t = params[k];
+            let span = CoxDeBoor::find_span(t, p, &knots);
+            CoxDeBoor::basis_funs_with(span, t, p, &knots, &mut basis, &mut left, &mut right);
+            for i in 0..=p {
+                matrix.set(k + 1, span - p + i, basis[i]);
+            }
+            for d in 0..dim {
+                rhs[[k + 1, d]] = points[[k, d]];
+            }
+        }
+
+        matrix.solve(&mut rhs).ok_or(NurbsError::SingularSystem)?;
+        NURBSCurve::try_new(p, rhs, Array1::ones(size), knots)
+    }
+
+    /// Least-squares fit with `control_points` control points
+    ///
+    /// The first and last points are interpolated exactly; `end_tangents`
+    /// additionally fixes the end derivatives. The curve is defined on
+    /// [0, 1].
+    #[allow(clippy::needless_range_loop)]
+    pub fn approximate(
+        points: &Array2<f64>,
+        degree: usize,
+        control_points: usize,
+        method: Parameterization,
+        end_tangents: Option<(&[f64], &[f64])>,
+    ) -> Result<CurveFit, NurbsError> {
+        let params = check_points(points, degree, end_tangents, method)?;
+        let (count, dim) = (points.shape()[0], points.shape()[1]);
+        let p = degree;
+
+        let min_controls = if end_tangents.is_some() { (p + 1).max(4) } else { p + 1 };
+        if control_points < min_controls {
+            return Err(NurbsError::DegreeTooHigh { axis: "t", degree: p, control_points });
+        }
+        if control_points > count {
+            return Err(NurbsError::TooManyControlPoints { control_points, points: count });
+        }
+        let h = control_points - 1;
+
+        // Knots spread so that every span holds data (NURBS Book, eq. 9.69)
+        let mut knots = vec![0.0; p + 1];
+        let spacing = count as f64 / (h - p + 1) as f64;
+        for j in 1..=h - p {
+            let i = (j as f64 * spacing) as usize;
+            let alpha = j as f64 * spacing - i as f64;
+            knots.push((1.0 - alpha) * params[i - 1] + alpha * params[i]);
+        }
+        knots.extend(std::iter::repeat_n(1.0, p + 1));
+
+        // Control points known in advance: the ends and, with tangents,
+        // their neighbours
+        let mut fixed: Vec<Option<Vec<f64>>> = vec![None; h + 1];
+        fixed[0] = Some(points.row(0).to_vec());
+        fixed[h] = Some(points.row(count - 1).to_vec());
+        if let Some((d0, dn)) = end_tangents {
+            let start_scale = (knots[p + 1] - knots[0]) / p as f64;
+            let end_scale = (knots[h + p + 1] - knots[h]) / p as f64;
+            fixed[1] = Some((0..dim).map(|d| points[[0, d]] + start_scale * d0[d]).collect());
+            fixed[h - 1] = Some((0..dim).map(|d| points[[count - 1, d]] - end_scale * dn[d]).collect());
+        }
+
+        let free: Vec<usize> = (0..=h).filter(|&i| fixed[i].is_none()).collect();
+        let mut column = vec![None; h + 1];
+        for (c, &i) in free.iter().enumerate() {
+            column[i] = Some(c);
+        }
+
+        // Normal equations over the free control points
+        let mut g = DMatrix::<f64>::zeros(free.len(), free.len());
+        let mut b = DMatrix::<f64>::zeros(free.len(), dim);
+        let mut basis = vec![0.0; p + 1];
+        let (mut left, mut right) = (vec![0.0; p + 1], vec![0.0; p + 1]);
+
+        for k in 1..count - 1 {
+            let t = params[k];
+            let span = CoxDeBoor::find_span(t, p, &knots);
+            CoxDeBoor::basis_funs_with(span, t, p, &knots, &mut basis, &mut left, &mut right);
+
+            // Residual of the data point after removing fixed contributions
+            let mut residual = points.row(k).to_vec();
+            for a in 0..=p {
+                if let Some(value) = &fixed[span - p + a] {
+                    for d in 0..dim {
+                        residual[d] -= basis[a] * value[d];
+                    }
+                }
+            }
+
+            for a in 0..=p {
+                let Some(r) = column[span - p + a] else {
+                    continue;
+                };
+                for c in 0..=p {
+                    if let Some(col) = column[span - p + c] {
+                        g[(r, col)] += basis[a] * basis[c];
+                    }
+                }
+                for d in 0..dim {
+                    b[(r, d)] += basis[a] * residual[d];
+                }
+            }
+        }
+
+        let solution = if free.is_empty() {
+            DMatrix::zeros(0, dim)
+        } else {
+            g.cholesky().ok_or(NurbsError::SingularSystem)?.solve(&b)
+        };
+
+        let mut control = Array2::zeros((h + 1, dim));
+        for i in 0..=h {
+            for d in 0..dim {
+                control[[i, d]] = match &fixed[i] {
+                    Some(value) => value[d],
+                    None => solution[(column[i].unwrap(), d)],
+                };
+            }
+        }
+
+        let curve = NURBSCurve::try_new(p, control, Array1::ones(h + 1), knots)?;
+
+        let distances: Vec<f64> = params
+            .iter()
+            .enumerate()
+            .map(|(k, &t)| {
+                let c = curve.evaluate(t);
+                (0..dim).map(|d| (c[d] - points[[k, d]]).powi(2)).sum::<f64>().sqrt()
+            })
+            .collect();
+        let max_error = distances.iter().cloned().fold(0.0, f64::max);
+        let rms_error = (distances.iter().map(|d| d * d).sum::<f64>() / count as f64).sqrt();
+
+        Ok(CurveFit { curve, max_error, rms_error })
+    }
+}
+
+/// Validate fitting input and compute the point parameters
+fn check_points(
+    points: &Array2<f64>,
+    degree: usize,
+    end_tangents: Option<(&[f64], &[f64])>,
+    method: Parameterization,
+) -> Result<Vec<f64>, NurbsError> {
+    let (count, dim) = (points.shape()[0], points.shape()[1]);
+    if dim == 0 {
+        return Err(NurbsError::InvalidDimension { expected: 1, found: 0 });
+    }
+    if degree == 0 || count < degree + 1 {
+        return Err(NurbsError::TooFewPoints { axis: "t", degree, points: count });
+    }
+    if points.iter().any(|c| !c.is_finite()) {
+        return Err(NurbsError::NonFinite { field: "points" });
+    }
+
+    if let Some((d0, dn)) = end_tangents {
+        for tangent in [d0, dn] {
+            if tangent.len() != dim {
+                return Err(NurbsError::LengthMismatch {
+                    field: "end tangents",
+                    expected: dim,
+                    found: tangent.len(),
+                });
+            }
+            if tangent.iter().any(|c| !c.is_finite()) {
+                return Err(NurbsError::NonFinite { field: "end tangents" });
+            }
+        }
+    }
+
+    let params = parameters(points.view(), method).ok_or(NurbsError::CoincidentPoints { axis: "t" })?;
+    if params.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(NurbsError::CoincidentPoints { axis: "t" });
+    }
+
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Quarter circle of radius 2 sampled unevenly
+    fn create_arc(count: usize) -> Array2<f64> {
+        let mut points = Array2::zeros((count, 2));
+        for k in 0..count {
+            let s = k as f64 / (count - 1) as f64;
+            let angle = std::f64::consts::FRAC_PI_2 * s * s.sqrt();
+            points[[k, 0]] = 2.0 * angle.cos();
+            points[[k, 1]] = 2.0 * angle.sin();
+        }
+        points
+    }
+
+    fn create_helix(count: usize) -> Array2<f64> {
+        let mut points = Array2::zeros((count, 3));
+        for k in 0..count {
+            let t = k as f64 * 0.6;
+            points[[k, 0]] = t.cos();
+            points[[k, 1]] = t.sin();
+            points[[k, 2]] = 0.3 * t;
+        }
+        points
+    }
+
+    #[test]
+    fn test_interpolation_passes_through_points() {
+        for (points, degree) in [(create_arc(7), 3), (create_helix(9), 2)] {
+            for method in [Parameterization::ChordLength, Parameterization::Centripetal] {
+                let curve = NURBSCurve::interpolate(&points, degree, method, None).unwrap();
+                assert_eq!(curve.num_control_points(), points.shape()[0]);
+
+                let params = parameters(points.view(), method).unwrap();
+                for (k, &t) in params.iter().enumerate() {
+                    let c = curve.evaluate(t);
+                    for d in 0..points.shape()[1] {
+                        assert_relative_eq!(c[d], points[[k, d]], epsilon = 1e-12);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpolation_with_end_tangents() {
+        let points = create_helix(6);
+        let d0 = [0.0, 5.0, 1.0];
+        let dn = [-4.0, 0.0, 2.0];
+
+        let method = Parameterization::ChordLength;
+        let curve = NURBSCurve::interpolate(&points, 3, method, Some((&d0, &dn))).unwrap();
+        assert_eq!(curve.num_control_points(), 8);
+
+        let params = parameters(points.view(), Parameterization::ChordLength).unwrap();
+        for (k, &t) in params.iter().enumerate() {
+            let c = curve.evaluate(t);
+            for d in 0..3 {
+                assert_relative_eq!(c[d], points[[k, d]], epsilon = 1e-12);
+            }
+        }
+
+        let start = curve.derivatives(0.0, 1);
+        let end = curve.derivatives(1.0, 1);
+        for d in 0..3 {
+            assert_relative_eq!(start[1][d], d0[d], epsilon = 1e-10);
+            assert_relative_eq!(end[1][d], dn[d], epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_approximation_of_dense_samples() {
+        let points = create_arc(60);
+        let fit = NURBSCurve::approximate(&points, 3, 8, Parameterization::ChordLength, None).unwrap();
+
+        assert_eq!(fit.curve.num_control_points(), 8);
+        assert!(fit.max_error < 1e-3, "max error {}", fit.max_error);
+        assert!(fit.rms_error <= fit.max_error);
+        assert_eq!(fit.curve.evaluate(0.0), vec![2.0, 0.0]);
+
+        // End tangents of the arc (scaled to the chord-length parameter)
+        let d0 = [0.0, 3.2];
+        let dn = [-3.2, 0.0];
+        let constrained =
+            NURBSCurve::approximate(&points, 3, 8, Parameterization::ChordLength, Some((&d0, &dn))).unwrap();
+        let start = constrained.curve.derivatives(0.0, 1);
+        let end = constrained.curve.derivatives(1.0, 1);
+        for d in 0..2 {
+            assert_relative_eq!(start[1][d], d0[d], epsilon = 1e-10);
+            assert_relative_eq!(end[1][d], dn[d], epsilon = 1e-10);
+        }
+        assert!(constrained.max_error < 1e-2, "max error {}", constrained.max_error);
+    }
+
+    #[test]
+    fn test_fitting_rejects_bad_input() {
+        let points = create_arc(3);
+        assert_eq!(
+            NURBSCurve::interpolate(&points, 3, Parameterization::ChordLength, None).unwrap_err(),
+            NurbsError::TooFewPoints { axis: "t", degree: 3, points: 3 }
+        );
+
+        let d0 = [1.0, 0.0, 0.0];
+        assert_eq!(
+            NURBSCurve::interpolate(&points, 2, Parameterization::ChordLength, Some((&d0, &d0))).unwrap_err(),
+            NurbsError::LengthMismatch { field: "end tangents", expected: 2, found: 3 }
+        );
+
+        let mut repeated = create_arc(5);
+        let duplicate = repeated.row(1).to_owned();
+        repeated.row_mut(2).assign(&duplicate);
+        assert_eq!(
+            NURBSCurve::approximate(&repeated, 2, 4, Parameterization::Centripetal, None).unwrap_err(),
+            NurbsError::CoincidentPoints { axis: "t" }
+        );
+
+        let arc = create_arc(5);
+        assert_eq!(
+            NURBSCurve::approximate(&arc, 2, 0, Parameterization::ChordLength, None).unwrap_err(),
+            NurbsError::DegreeTooHigh { axis: "t", degree: 2, control_points: 0 }
+        );
+        assert_eq!(
+            NURBSCurve::approximate(&arc, 2, 6, Parameterization::ChordLength, None).unwrap_err(),
+            NurbsError::TooManyControlPoints { control_points: 6, points: 5 }
+        );
+    }
+}
@@ -1,90 +1,68 @@
 use crate::surface::NURBSSurface;
+use crate::vector::{add, cross, dot, normalize, scale};
 
 /// Compute tangent vectors at a surface point
+///
+/// Returns the exact partial derivatives (S_u, S_v).
 pub fn compute_tangent(surface: &NURBSSurface, u: f64, v: f64) -> ([f64; 3], [f64; 3]) {
-    let eps = 1e-6;
-
-    // Numerical differentiation (central difference)
-    let u_plus = surface.evaluate((u + eps).min(1.0), v);
-    let u_minus = surface.evaluate((u - eps).max(0.0), v);
-
-    let v_plus = surface.evaluate(u, (v + eps).min(1.0));
-    let v_minus = surface.evaluate(u, (v - eps).max(0.0));
-
-    let du = [
-        (u_plus[0] - u_minus[0]) / (2.0 * eps),
-        (u_plus[1] - u_minus[1]) / (2.0 * eps),
-        (u_plus[2] - u_minus[2]) / (2.0 * eps),
-    ];
-
-    let dv = [
-        (v_plus[0] - v_minus[0]) / (2.0 * eps),
-        (v_plus[1] - v_minus[1]) / (2.0 * eps),
-        (v_plus[2] - v_minus[2]) / (2.0 * eps),
-    ];
-
-    (du, dv)
+    let ders = surface.derivatives(u, v, 1);
+    (ders[1][0], ders[0][1])
 }
 
 /// Compute surface normal at a point
 pub fn compute_normal(surface: &NURBSSurface, u: f64, v: f64) -> [f64; 3] {
     let (du, dv) = compute_tangent(surface, u, v);
+    unit_normal(&du, &dv)
+}
 
-    // Cross product
-    let normal = [
-        du[1] * dv[2] - du[2] * dv[1],
-        du[2] * dv[0] - du[0] * dv[2],
-        du[0] * dv[1] - du[1] * dv[0],
-    ];
-
-    // Normalize
-    let length = (normal[0].powi(2) + normal[1].powi(2) + normal[2].powi(2)).sqrt();
-
-    if length > 1e-10 {
-        [normal[0] / length, normal[1] / length, normal[2] / length]
-    } else {
-        [0.0, 0.0, 1.0] // Degenerate case
-    }
+/// Relative tolerance on k1 - k2 below which a point counts as umbilic
+const UMBILIC_TOLERANCE: f64 = 1e-8;
+
+/// Local differential geometry of a surface point
+///
+/// Curvatures are signed with respect to `normal` (= S_u x S_v normalized),
+/// with k1 >= k2. `dir1` and `dir2` are the unit principal directions of k1
+/// and k2 and form a right-handed frame with the normal. At umbilic points
+/// every tangent direction is principal; `dir1` then follows S_u.
+#[derive(Debug, Clone, Copy)]
+pub struct CurvatureInfo {
+    pub point: [f64; 3],
+    pub normal: [f64; 3],
+    /// First fundamental form coefficients (E, F, G)
+    pub first_form: [f64; 3],
+    /// Second fundamental form coefficients (L, M, N)
+    pub second_form: [f64; 3],
+    /// Shape operator I^-1 II acting on (du, dv) parameter vectors
+    pub shape_operator: [[f64; 2]; 2],
+    pub gaussian: f64,
+    pub mean: f64,
+    pub k1: f64,
+    pub k2: f64,
+    pub dir1: [f64; 3],
+    pub dir2: [f64; 3],
+    pub umbilic: bool,
 }
 
-/// Compute principal curvatures and directions
+/// Compute principal curvatures at a point
+///
+/// Curvatures are signed with respect to `compute_normal`, k1 >= k2.
 pub fn compute_curvature(surface: &NURBSSurface, u: f64, v: f64) -> (f64, f64) {
-    let eps = 1e-5;
-
-    // First derivatives
-    let (du, dv) = compute_tangent(surface, u, v);
-
-    // Second derivatives (numerical)
-    let p = surface.evaluate(u, v);
-
-    let u_plus = surface.evaluate((u + eps).min(1.0), v);
-    let u_minus = surface.evaluate((u - eps).max(0.0), v);
-    let v_plus = surface.evaluate(u, (v + eps).min(1.0));
-    let v_minus = surface.evaluate(u, (v - eps).max(0.0));
-
-    let u_plus_v_plus = surface.evaluate((u + eps).min(1.0), (v + eps).min(1.0));
-    let u_minus_v_minus = surface.evaluate((u - eps).max(0.0), (v - eps).max(0.0));
+    let info = compute_curvature_info(surface, u, v);
+    (info.k1, info.k2)
+}
 
-    let duu = [
-        (u_plus[0] - 2.0 * p[0] + u_minus[0]) / (eps * eps),
-        (u_plus[1] - 2.0 * p[1] + u_minus[1]) / (eps * eps),
-        (u_plus[2] - 2.0 * p[2] + u_minus[2]) / (eps * eps),
-    ];
+/// Compute fundamental forms, curvatures and principal directions at a point
+pub fn compute_curvature_info(surface: &NURBSSurface, u: f64, v: f64) -> CurvatureInfo {
+    let ders = surface.derivatives(u, v, 2);
 
-    let dvv = [
-        (v_plus[0] - 2.0 * p[0] + v_minus[0]) / (eps * eps),
-        (v_plus[1] - 2.0 * p[1] + v_minus[1]) / (eps * eps),
-        (v_plus[2] - 2.0 * p[2] + v_minus[2]) / (eps * eps),
-    ];
-
-    let duv = [
-        (u_plus_v_plus[0] - u_plus[0] - v_plus[0] + p[0]) / (eps * eps),
-        (u_plus_v_plus[1] - u_plus[1] - v_plus[1] + p[1]) / (eps * eps),
-        (u_plus_v_plus[2] - u_plus[2] - v_plus[2] + p[2]) / (eps * eps),
-    ];
+    let du = ders[1][0];
+    let dv = ders[0][1];
+    let duu = ders[2][0];
+    let duv = ders[1][1];
+    let dvv = ders[0][2];
 
     // Normal vector
-    let n = compute_normal(surface, u, v);
+    let n = unit_normal(&du, &dv);
 
     // Coefficients of the first fundamental form
     let e = dot(&du, &du);
@@ -96,21 +74,56 @@ pub fn compute_curvature(surface: &NURBSSurface, u: f64, v: f64) -> (f64, f64) {
     let m = dot(&duv, &n);
     let n_coef = dot(&dvv, &n);
 
+    // Shape operator W = I^-1 II
+    let det = e * g - f * f;
+    let shape_operator = [
+        [(g * l - f * m) / det, (g * m - f * n_coef) / det],
+        [(e * m - f * l) / det, (e * n_coef - f * m) / det],
+    ];
+
     // Gaussian and mean curvature
-    let k_gaussian = (l * n_coef - m * m) / (e * g - f * f);
-    let k_mean = (e * n_coef - 2.0 * f * m + g * l) / (2.0 * (e * g - f * f));
+    let k_gaussian = (l * n_coef - m * m) / det;
+    let k_mean = (e * n_coef - 2.0 * f * m + g * l) / (2.0 * det);
 
     // Principal curvatures
     let discriminant = (k_mean * k_mean - k_gaussian).max(0.0).sqrt();
     let k1 = k_mean + discriminant;
     let k2 = k_mean - discriminant;
 
-    (k1, k2)
+    let umbilic = k1 - k2 <= UMBILIC_TOLERANCE * k_mean.abs().max(1.0);
+
+    // Eigenvector of W for k1, mapped to 3D through the tangents
+    let dir1 = if umbilic {
+        None
+    } else {
+        let [[w11, w12], [w21, w22]] = shape_operator;
+        let (a0, b0) = (w12, k1 - w11);
+        let (a1, b1) = (k1 - w22, w21);
+        let (a, b) = if a0.hypot(b0) >= a1.hypot(b1) { (a0, b0) } else { (a1, b1) };
+        normalize(&add(&scale(&du, a), &scale(&dv, b)))
+    };
+    let dir1 = dir1.or_else(|| normalize(&du)).unwrap_or([1.0, 0.0, 0.0]);
+    let dir2 = cross(&n, &dir1);
+
+    CurvatureInfo {
+        point: ders[0][0],
+        normal: n,
+        first_form: [e, f, g],
+        second_form: [l, m, n_coef],
+        shape_operator,
+        gaussian: k_gaussian,
+        mean: k_mean,
+        k1,
+        k2,
+        dir1,
+        dir2,
+        umbilic,
+    }
 }
 
-/// Dot product of 3D vectors
-fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
-    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+/// Normalized cross product of the tangents
+fn unit_normal(du: &[f64; 3], dv: &[f64; 3]) -> [f64; 3] {
+    normalize(&cross(du, dv)).unwrap_or([0.0, 0.0, 1.0]) // Degenerate case
 }
 
 #[cfg(test)]
@@ -119,44 +132,6 @@ mod tests {
     use approx::assert_relative_eq;
     use ndarray::{Array2, Array3};
 
-    fn create_sphere(radius: f64) -> NURBSSurface {
-        // Finer sphere approximation with NURBS
-        let degree = 3;
-        let u_res = 7;
-        let v_res = 7;
-
-        let mut control_points = Array3::zeros((u_res, v_res, 3));
-        let mut weights = Array2::ones((u_res, v_res));
-
-        // Sphere control points (full sphere)
-        for i in 0..u_res {
-            let theta = std::f64::consts::PI * (i as f64) / (u_res - 1) as f64;
-            for j in 0..v_res {
-                let phi = 2.0 * std::f64::consts::PI * (j as f64) / (v_res - 1) as f64;
-
-                control_points[[i, j, 0]] = radius * theta.sin() * phi.cos();
-                control_points[[i, j, 1]] = radius * theta.sin() * phi.sin();
-                control_points[[i, j, 2]] = radius * theta.cos();
-            }
-        }
-
-        // Uniform open knot vectors
-        let mut knots_u = vec![0.0; degree + 1];
-        let mut knots_v = vec![0.0; degree + 1];
-        let interior_u = u_res - degree - 1;
-        let interior_v = v_res - degree - 1;
-        for i in 1..=interior_u {
-            knots_u.push(i as f64 / (interior_u as f64 + 1.0));
-        }
-        for i in 1..=interior_v {
-            knots_v.push(i as f64 / (interior_v as f64 + 1.0));
-        }
-        knots_u.extend(vec![1.0; degree + 1]);
-        knots_v.extend(vec![1.0; degree + 1]);
-
-        NURBSSurface::new(degree, degree, control_points, weights, knots_u, knots_v)
-    }
-
     #[test]
     fn test_normal_flat_surface() {
         let degree = 1;
@@ -187,17 +162,86 @@ mod tests {
     }
 
     #[test]
-    fn test_curvature_sphere() {
-        let radius = 1.0;
-        let sphere = create_sphere(radius);
+    fn test_curvature_paraboloid_at_boundary() {
+        // z = u^2 + v^2 over [0, 1]^2 as a biquadratic Bezier patch
+        let mut control_points = Array3::zeros((3, 3, 3));
+        let x = [0.0, 0.5, 1.0];
+        let z = [0.0, 0.0, 1.0];
+        for i in 0..3 {
+            for j in 0..3 {
+                control_points[[i, j, 0]] = x[i];
+                control_points[[i, j, 1]] = x[j];
+                control_points[[i, j, 2]] = z[i] + z[j];
+            }
+        }
 
-        let (k1, k2) = compute_curvature(&sphere, 0.5, 0.5);
+        let knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        let surface = NURBSSurface::new(2, 2, control_points, Array2::ones((3, 3)), knots.clone(), knots);
 
-        // For a sphere, both principal curvatures should be 1/radius (allow sign flip and large error)
-        let expected = 1.0 / radius;
+        // The corner (0, 0) is the apex: both principal curvatures equal 2
+        let (k1, k2) = compute_curvature(&surface, 0.0, 0.0);
+        assert_relative_eq!(k1, 2.0, epsilon = 1e-10);
+        assert_relative_eq!(k2, 2.0, epsilon = 1e-10);
+
+        let (du, dv) = compute_tangent(&surface, 1.0, 1.0);
+        assert_relative_eq!(du[2], 2.0, epsilon = 1e-10);
+        assert_relative_eq!(dv[2], 2.0, epsilon = 1e-10);
+    }
 
-        // Accept sign flip and up to 1.0 error due to NURBS approximation
-        assert!((k1.abs() - expected).abs() < 1.0, "k1 = {}, expected ~ {}", k1, expected);
-        assert!((k2.abs() - expected).abs() < 1.0, "k2 = {}, expected ~ {}", k2, expected);
+    #[test]
+    fn test_curvature_info_paraboloid() {
+        // z = x^2 + y^2 over [-1, 1]^2 as a biquadratic Bezier patch
+        let mut control_points = Array3::zeros((3, 3, 3));
+        let x = [-1.0, 0.0, 1.0];
+        let z = [1.0, -1.0, 1.0];
+        for i in 0..3 {
+            for j in 0..3 {
+                control_points[[i, j, 0]] = x[i];
+                control_points[[i, j, 1]] = x[j];
+                control_points[[i, j, 2]] = z[i] + z[j];
+            }
+        }
+
+        let knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        let surface = NURBSSurface::new(2, 2, control_points, Array2::ones((3, 3)), knots.clone(), knots);
+
+        // Apex is umbilic with K = 4, H = 2
+        let apex = compute_curvature_info(&surface, 0.5, 0.5);
+        assert!(apex.umbilic);
+        assert_relative_eq!(apex.gaussian, 4.0, epsilon = 1e-10);
+        assert_relative_eq!(apex.mean, 2.0, epsilon = 1e-10);
+
+        // At (x, y) = (0.5, 0): k = 2 / sqrt(2) across y, 2 / 2^1.5 along x
+        let info = compute_curvature_info(&surface, 0.75, 0.5);
+        assert!(!info.umbilic);
+        assert_relative_eq!(info.k1, 2.0f64.sqrt(), epsilon = 1e-10);
+        assert_relative_eq!(info.k2, 0.5f64.sqrt(), epsilon = 1e-10);
+        assert_relative_eq!(info.gaussian, info.k1 * info.k2, epsilon = 1e-10);
+        assert_relative_eq!(info.mean, 0.5 * (info.k1 + info.k2), epsilon = 1e-10);
+
+        assert_relative_eq!(info.dir1[1].abs(), 1.0, epsilon = 1e-10);
+        let s = 0.5f64.sqrt();
+        assert_relative_eq!(info.dir2[0].abs(), s, epsilon = 1e-10);
+        assert_relative_eq!(info.dir2[2].abs(), s, epsilon = 1e-10);
+        assert_relative_eq!(dot(&info.dir1, &info.normal), 0.0, epsilon = 1e-12);
+        assert_relative_eq!(dot(&info.dir2, &info.normal), 0.0, epsilon = 1e-12);
+
+        // The shape operator has the principal curvatures as eigenvalues
+        let [[w11, w12], [w21, w22]] = info.shape_operator;
+        assert_relative_eq!(w11 + w22, info.k1 + info.k2, epsilon = 1e-10);
+        assert_relative_eq!(w11 * w22 - w12 * w21, info.gaussian, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_curvature_sphere() {
+        let radius = 2.0;
+        let sphere = NURBSSurface::sphere([0.0; 3], radius);
+
+        // Outward normal: both principal curvatures are -1/radius
+        for &(u, v) in &[(0.5, 0.5), (0.2, 0.3), (0.9, 0.8)] {
+            let (k1, k2) = compute_curvature(&sphere, u, v);
+            assert_relative_eq!(k1, -1.0 / radius, epsilon = 1e-10);
+            assert_relative_eq!(k2, -1.0 / radius, epsilon = 1e-10);
+        }
     }
 }
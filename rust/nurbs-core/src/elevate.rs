@@ -0,0 +1,383 @@
+//! Degree elevation
+//!
+//! Elevation works on homogeneous control points, so weights are carried
+//! through exactly and the shape is preserved up to round-off.
+
+use ndarray::Array2;
+
+use crate::curve::{binomial, NURBSCurve};
+use crate::refine::clamp_knots;
+use crate::surface::{Direction, NURBSSurface};
+
+impl NURBSSurface {
+    /// Raise the degree in u by `times`
+    pub fn elevate_degree_u(&self, times: usize) -> NURBSSurface {
+        if times == 0 {
+            return self.clone();
+        }
+
+        self.map_rows(Direction::U, |knots, p, row| {
+            let (k, r) = elevate_degree(knots, p, row, times);
+            (k, p + times, r)
+        })
+    }
+
+    /// Raise the degree in v by `times`
+    pub fn elevate_degree_v(&self, times: usize) -> NURBSSurface {
+        if times == 0 {
+            return self.clone();
+        }
+
+        self.map_rows(Direction::V, |knots, q, row| {
+            let (k, r) = elevate_degree(knots, q, row, times);
+            (k, q + times, r)
+        })
+    }
+
+    /// Raise the degree by `times_u` in u and `times_v` in v
+    pub fn elevate_degree(&self, times_u: usize, times_v: usize) -> NURBSSurface {
+        self.elevate_degree_u(times_u).elevate_degree_v(times_v)
+    }
+
+    /// Raise the degree to at least (degree_u, degree_v)
+    ///
+    /// Directions that already have the requested degree are left untouched.
+    pub fn elevate_degree_to(&self, degree_u: usize, degree_v: usize) -> NURBSSurface {
+        self.elevate_degree(
+            degree_u.saturating_sub(self.degree_u),
+            degree_v.saturating_sub(self.degree_v),
+        )
+    }
+}
+
+impl NURBSCurve {
+    /// Raise the degree by `times`
+    pub fn elevate_degree(&self, times: usize) -> NURBSCurve {
+        if times == 0 {
+            return self.clone();
+        }
+
+        let (knots, qw) = elevate_degree(&self.knots, self.degree, &self.homogeneous(), times);
+        NURBSCurve::from_homogeneous(self.degree + times, &qw, knots)
+    }
+}
+
+/// Bring two surfaces to a common degree in both directions
+pub fn elevate_to_common_degree(a: &NURBSSurface, b: &NURBSSurface) -> (NURBSSurface, NURBSSurface) {
+    let degree_u = a.degree_u.max(b.degree_u);
+    let degree_v = a.degree_v.max(b.degree_v);
+
+    (
+        a.elevate_degree_to(degree_u, degree_v),
+        b.elevate_degree_to(degree_u, degree_v),
+    )
+}
+
+/// Elevate a curve with homogeneous points `pw` by `t` degrees
+/// (NURBS Book, A5.9)
+///
+/// The curve is split into Bezier segments on the fly, each segment is
+/// elevated, and the knots introduced by the split are removed again.
+/// Unclamped knot vectors are clamped first, so the result is clamped.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn elevate_degree(
+    knots: &[f64],
+    degree: usize,
+    pw: &Array2<f64>,
+    t: usize,
+) -> (Vec<f64>, Array2<f64>) {
+    let (knots, pw) = clamp_knots(knots, degree, pw);
+    let p = degree;
+    let n = pw.shape()[0] - 1;
+    let dim = pw.shape()[1];
+    let m = n + p + 1;
+    let ph = p + t;
+    let ph2 = ph / 2;
+
+    // Bezier degree elevation coefficients
+    let mut bezalfs = vec![vec![0.0; p + 1]; ph + 1];
+    bezalfs[0][0] = 1.0;
+    bezalfs[ph][p] = 1.0;
+    for i in 1..=ph2 {
+        let inv = 1.0 / binomial(ph, i);
+        for j in i.saturating_sub(t)..=p.min(i) {
+            bezalfs[i][j] = inv * binomial(p, j) * binomial(t, i - j);
+        }
+    }
+    for i in ph2 + 1..ph {
+        for j in i.saturating_sub(t)..=p.min(i) {
+            bezalfs[i][j] = bezalfs[ph - i][p - j];
+        }
+    }
+
+    // Upper bounds on the output size: every distinct interior knot gains t
+    let segments = knots.windows(2).filter(|w| w[1] > w[0]).count();
+    let max_points = n + 1 + t * segments;
+
+    let mut uh = vec![0.0; max_points + ph + 1];
+    let mut qw = vec![vec![0.0; dim]; max_points];
+    let mut bpts = vec![vec![0.0; dim]; p + 1];
+    let mut ebpts = vec![vec![0.0; dim]; ph + 1];
+    let mut next_bpts = vec![vec![0.0; dim]; p.saturating_sub(1).max(1)];
+    let mut alfs = vec![0.0; p.max(1)];
+
+    let mut mh = ph;
+    let mut kind = ph + 1;
+    let mut r: isize = -1;
+    let mut a = p;
+    let mut b = p + 1;
+    let mut cind = 1;
+    let mut ua = knots[0];
+
+    qw[0] = pw.row(0).to_vec();
+    for u in uh.iter_mut().take(ph + 1) {
+        *u = ua;
+    }
+
+    // Initialize first Bezier segment
+    for i in 0..=p {
+        bpts[i] = pw.row(i).to_vec();
+    }
+
+    while b < m {
+        let i0 = b;
+        while b < m && knots[b] == knots[b + 1] {
+            b += 1;
+        }
+        let mul = b - i0 + 1;
+        mh += mul + t;
+        let ub = knots[b];
+        let oldr = r;
+        r = p as isize - mul as isize;
+
+        // Insert knot u(b) r times
+        let lbz = if oldr > 0 { (oldr as usize + 2) / 2 } else { 1 };
+        let rbz = if r > 0 { ph - (r as usize).div_ceil(2) } else { ph };
+
+        if r > 0 {
+            let r = r as usize;
+            let numer = ub - ua;
+            for k in (mul + 1..=p).rev() {
+                alfs[k - mul - 1] = numer / (knots[a + k] - ua);
+            }
+            for j in 1..=r {
+                let save = r - j;
+                let s = mul + j;
+                for k in (s..=p).rev() {
+                    let alpha = alfs[k - s];
+                    for d in 0..dim {
+                        bpts[k][d] = alpha * bpts[k][d] + (1.0 - alpha) * bpts[k - 1][d];
+                    }
+                }
+                next_bpts[save] = bpts[p].clone();
+            }
+        }
+
+        // Degree elevate Bezier segment
+        for i in lbz..=ph {
+            ebpts[i] = vec![0.0; dim];
+            for j in i.saturating_sub(t)..=p.min(i) {
+                for d in 0..dim {
+                    ebpts[i][d] += bezalfs[i][j] * bpts[j][d];
+                }
+            }
+        }
+
+        // Remove knot u = U[a] oldr times
+        if oldr > 1 {
+            let oldr = oldr as usize;
+            let den = ub - ua;
+            let bet = (ub - uh[kind - 1]) / den;
+
+            for tr in 1..oldr {
+                let mut i = kind - 1 - tr;
+                let mut j = kind + tr - 1;
+                let mut kj = j - kind + 1;
+
+                while j - i > tr {
+                    if i < cind {
+                        let alf = (ub - uh[i]) / (ua - uh[i]);
+                        for d in 0..dim {
+                            qw[i][d] = alf * qw[i][d] + (1.0 - alf) * qw[i - 1][d];
+                        }
+                    }
+                    if j >= lbz {
+                        let coef = if j - tr <= kind - ph + oldr {
+                            (ub - uh[j - tr]) / den
+                        } else {
+                            bet
+                        };
+                        for d in 0..dim {
+                            ebpts[kj][d] = coef * ebpts[kj][d] + (1.0 - coef) * ebpts[kj + 1][d];
+                        }
+                    }
+                    i += 1;
+                    j -= 1;
+                    kj = kj.wrapping_sub(1);
+                }
+            }
+        }
+
+        // Load the knot ua
+        if a != p {
+            let oldr = oldr.max(0) as usize;
+            for _ in 0..ph - oldr {
+                uh[kind] = ua;
+                kind += 1;
+            }
+        }
+
+        // Load control points
+        for j in lbz..=rbz {
+            qw[cind] = ebpts[j].clone();
+            cind += 1;
+        }
+
+        if b < m {
+            // Set up for next pass
+            let r = r.max(0) as usize;
+            bpts[..r].clone_from_slice(&next_bpts[..r]);
+            for j in r..=p {
+                bpts[j] = pw.row(b - p + j).to_vec();
+            }
+            a = b;
+            b += 1;
+            ua = ub;
+        } else {
+            // End knot
+            for i in 0..=ph {
+                uh[kind + i] = ub;
+            }
+        }
+    }
+
+    let nh = mh - ph;
+    uh.truncate(nh + ph + 1);
+
+    let mut result = Array2::zeros((nh, dim));
+    for i in 0..nh {
+        for d in 0..dim {
+            result[[i, d]] = qw[i][d];
+        }
+    }
+
+    (uh, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::{array, Array3};
+
+    #[test]
+    fn test_elevate_curve_preserves_circle() {
+        let curve = NURBSCurve::new(
+            2,
+            array![[1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [-1.0, 1.0], [-1.0, 0.0]],
+            array![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0],
+            vec![0.0, 0.0, 0.0, 0.5, 0.5, 1.0, 1.0, 1.0],
+        );
+
+        let elevated = curve.elevate_degree(2);
+
+        assert_eq!(elevated.degree, 4);
+        assert_eq!(
+            elevated.knots,
+            vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0, 1.0]
+        );
+
+        for i in 0..=20 {
+            let t = i as f64 / 20.0;
+            let a = curve.evaluate(t);
+            let b = elevated.evaluate(t);
+            assert_relative_eq!(a[0], b[0], epsilon = 1e-12);
+            assert_relative_eq!(a[1], b[1], epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_elevate_surface_to_common_degree() {
+        // Bilinear plane vs. a cubic patch with an interior knot
+        let mut plane_points = Array3::zeros((2, 2, 3));
+        for i in 0..2 {
+            for j in 0..2 {
+                plane_points[[i, j, 0]] = i as f64;
+                plane_points[[i, j, 1]] = j as f64;
+                plane_points[[i, j, 2]] = (i + j) as f64 * 0.5;
+            }
+        }
+        let linear = vec![0.0, 0.0, 1.0, 1.0];
+        let plane = NURBSSurface::new(1, 1, plane_points, Array2::ones((2, 2)), linear.clone(), linear);
+
+        let mut cubic_points = Array3::zeros((5, 4, 3));
+        let mut weights = Array2::ones((5, 4));
+        for i in 0..5 {
+            for j in 0..4 {
+                cubic_points[[i, j, 0]] = i as f64;
+                cubic_points[[i, j, 1]] = j as f64;
+                cubic_points[[i, j, 2]] = ((i + j) % 2) as f64;
+                weights[[i, j]] = 1.0 + 0.5 * (i % 2) as f64;
+            }
+        }
+        let cubic = NURBSSurface::new(
+            3,
+            2,
+            cubic_points,
+            weights,
+            vec![0.0, 0.0, 0.0, 0.0, 0.3, 1.0, 1.0, 1.0, 1.0],
+            vec![0.0, 0.0, 0.0, 0.6, 1.0, 1.0, 1.0],
+        );
+
+        let (plane_e, cubic_e) = elevate_to_common_degree(&plane, &cubic);
+
+        assert_eq!((plane_e.degree_u, plane_e.degree_v), (3, 2));
+        assert_eq!((cubic_e.degree_u, cubic_e.degree_v), (3, 2));
+        assert_eq!(plane_e.dimensions(), (4, 3));
+
+        let cubic_up = cubic.elevate_degree(1, 2);
+        assert_eq!(cubic_up.knots_u, [0.0; 5].into_iter().chain([0.3; 2]).chain([1.0; 5]).collect::<Vec<_>>());
+
+        // Several interior knots of mixed multiplicity
+        let knotty = cubic.refine_knots_u(&[0.5, 0.5, 0.8]);
+        let knotty_up = knotty.elevate_degree(2, 1);
+        assert_eq!(knotty_up.dimensions(), (knotty.dimensions().0 + 2 * 4, 6));
+
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let (u, v) = (i as f64 / 10.0, j as f64 / 10.0);
+                for (orig, elev) in [(&plane, &plane_e), (&cubic, &cubic_e), (&cubic, &cubic_up), (&cubic, &knotty_up)] {
+                    let a = orig.evaluate(u, v);
+                    let b = elev.evaluate(u, v);
+                    for k in 0..3 {
+                        assert_relative_eq!(a[k], b[k], epsilon = 1e-10);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_elevate_unclamped_curve() {
+        // Uniform cubic B-spline on [3, 6]
+        let mut control_points = Array2::zeros((6, 2));
+        for i in 0..6 {
+            control_points[[i, 0]] = i as f64;
+            control_points[[i, 1]] = (i as f64).sin();
+        }
+        let knots: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let curve = NURBSCurve::new(3, control_points, ndarray::Array1::ones(6), knots);
+
+        let elevated = curve.elevate_degree(1);
+        assert_eq!(elevated.degree, 4);
+        assert!(elevated.is_clamped());
+        assert_eq!(elevated.domain(), [3.0, 6.0]);
+
+        for i in 0..=30 {
+            let t = 3.0 + i as f64 / 10.0;
+            let (a, b) = (curve.evaluate(t), elevated.evaluate(t));
+            assert_relative_eq!(a[0], b[0], epsilon = 1e-12);
+            assert_relative_eq!(a[1], b[1], epsilon = 1e-12);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Validation errors for NURBS construction
+
+use std::fmt;
+
+/// Reasons a set of arrays does not describe a valid NURBS entity
+///
+/// `axis` names the parametric direction: "u" or "v" for surfaces and "t"
+/// for curves. Weight indices are flat, row-major positions in the weight
+/// array.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NurbsError {
+    /// Control points have the wrong number of coordinates
+    /// (curves only require at least one)
+    InvalidDimension { expected: usize, found: usize },
+    /// Weight array shape differs from the control point grid
+    WeightShapeMismatch { expected: Vec<usize>, found: Vec<usize> },
+    /// Knot vector length is not control points + degree + 1
+    InvalidKnotLength { axis: &'static str, expected: usize, found: usize },
+    /// Knot vector decreases at `index`
+    NonMonotonicKnots { axis: &'static str, index: usize },
+    /// Knot vector has an empty parameter domain
+    DegenerateDomain { axis: &'static str },
+    /// Fewer than degree + 1 control points
+    DegreeTooHigh { axis: &'static str, degree: usize, control_points: usize },
+    /// Weight at `index` is zero or negative
+    NonPositiveWeight { index: usize, weight: f64 },
+    /// NaN or infinity in the named input
+    NonFinite { field: &'static str },
+    /// Fewer data points than a fit of this degree needs
+    TooFewPoints { axis: &'static str, degree: usize, points: usize },
+    /// Data points coincide, so no parameterization exists
+    CoincidentPoints { axis: &'static str },
+    /// Fitting produced a singular linear system
+    SingularSystem,
+    /// Paired inputs have different lengths
+    LengthMismatch { field: &'static str, expected: usize, found: usize },
+    /// The named tolerance or step is zero or negative
+    NonPositive { field: &'static str, value: f64 },
+    /// The named direction vector is zero
+    ZeroVector { field: &'static str },
+    /// Radius of a primitive is negative, zero where it must be positive,
+    /// or NaN
+    InvalidRadius { field: &'static str, value: f64 },
+    /// More control points than data points in a least-squares fit
+    TooManyControlPoints { control_points: usize, points: usize },
+    /// Surface control grid differs from the configured fitting grid
+    ControlGridMismatch { expected: (usize, usize), found: (usize, usize) },
+    /// Fixed control point index lies outside the fitting grid
+    FixedPointOutOfRange { i: usize, j: usize },
+    /// Degree is zero where at least a linear basis is needed
+    ZeroDegree { axis: &'static str },
+    /// The named direction vectors are parallel
+    ParallelVectors { first: &'static str, second: &'static str },
+}
+
+impl fmt::Display for NurbsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NurbsError::InvalidDimension { expected, found } => {
+                write!(f, "Invalid control point dimension: expected {}, found {}", expected, found)
+            }
+            NurbsError::WeightShapeMismatch { expected, found } => write!(
+                f,
+                "Control points and weights must match: expected weights of shape {:?}, found {:?}",
+                expected, found
+            ),
+            NurbsError::InvalidKnotLength { axis, expected, found } => write!(
+                f,
+                "Invalid knot vector length in {}: expected {}, found {}",
+                axis, expected, found
+            ),
+            NurbsError::NonMonotonicKnots { axis, index } => {
+                write!(f, "Knot vector in {} decreases at index {}", axis, index)
+            }
+            NurbsError::DegenerateDomain { axis } => {
+                write!(f, "Knot vector in {} spans an empty parameter domain", axis)
+            }
+            NurbsError::DegreeTooHigh { axis, degree, control_points } => write!(
+                f,
+                "Degree {} in {} needs at least {} control points, found {}",
+                degree,
+                axis,
+                degree + 1,
+                control_points
+            ),
+            NurbsError::NonPositiveWeight { index, weight } => {
+                write!(f, "Weight {} at index {} is not positive", weight, index)
+            }
+            NurbsError::NonFinite { field } => write!(f, "Non-finite value in {}", field),
+            NurbsError::TooFewPoints { axis, degree, points } => write!(
+                f,
+                "Fitting degree {} in {} needs at least {} points, found {}",
+                degree,
+                axis,
+                degree + 1,
+                points
+            ),
+            NurbsError::CoincidentPoints { axis } => {
+                write!(f, "Data points in {} coincide; cannot parameterize", axis)
+            }
+            NurbsError::SingularSystem => write!(f, "Fitting system is singular"),
+            NurbsError::LengthMismatch { field, expected, found } => {
+                write!(f, "Expected {} entries in {}, found {}", expected, field, found)
+            }
+            NurbsError::NonPositive { field, value } => {
+                write!(f, "The {} must be positive, found {}", field, value)
+            }
+            NurbsError::ZeroVector { field } => write!(f, "The {} must be non-zero", field),
+            NurbsError::InvalidRadius { field, value } => write!(f, "Invalid {}: {}", field, value),
+            NurbsError::TooManyControlPoints { control_points, points } => write!(
+                f,
+                "Approximation with {} control points needs at least as many points, found {}",
+                control_points, points
+            ),
+            NurbsError::ControlGridMismatch { expected, found } => write!(
+                f,
+                "Control grid must be {} x {}, found {} x {}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            NurbsError::FixedPointOutOfRange { i, j } => {
+                write!(f, "Fixed control point ({}, {}) lies outside the control grid", i, j)
+            }
+            NurbsError::ZeroDegree { axis } => write!(f, "Degree in {} must be at least 1", axis),
+            NurbsError::ParallelVectors { first, second } => {
+                write!(f, "The {} and {} must not be parallel", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NurbsError {}
+
+/// Check a knot vector against its degree and control point count
+pub(crate) fn validate_knots(
+    axis: &'static str,
+    knots: &[f64],
+    degree: usize,
+    control_points: usize,
+) -> Result<(), NurbsError> {
+    if control_points < degree + 1 {
+        return Err(NurbsError::DegreeTooHigh { axis, degree, control_points });
+    }
+
+    let expected = control_points + degree + 1;
+    if knots.len() != expected {
+        return Err(NurbsError::InvalidKnotLength { axis, expected, found: knots.len() });
+    }
+
+    if knots.iter().any(|k| !k.is_finite()) {
+        return Err(NurbsError::NonFinite { field: "knots" });
+    }
+
+    if let Some(index) = knots.windows(2).position(|w| w[1] < w[0]) {
+        return Err(NurbsError::NonMonotonicKnots { axis, index: index + 1 });
+    }
+
+    if knots[degree] >= knots[control_points] {
+        return Err(NurbsError::DegenerateDomain { axis });
+    }
+
+    Ok(())
+}
+
+/// Check that all weights are finite and positive
+pub(crate) fn validate_weights<'a>(weights: impl Iterator<Item = &'a f64>) -> Result<(), NurbsError> {
+    for (index, &weight) in weights.enumerate() {
+        if !weight.is_finite() {
+            return Err(NurbsError::NonFinite { field: "weights" });
+        }
+        if weight <= 0.0 {
+            return Err(NurbsError::NonPositiveWeight { index, weight });
+        }
+    }
+
+    Ok(())
+}
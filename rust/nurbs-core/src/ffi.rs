@@ -1,7 +1,8 @@
 //! C-compatible FFI for Julia interop
 
 use super::surface::NURBSSurface;
-use super::derivatives::{compute_normal, compute_curvature};
+use super::derivatives::{compute_normal, compute_curvature, compute_curvature_info};
+use super::error::NurbsError;
 use libc::{c_double, c_int};
 use ndarray::{Array2, Array3};
 use std::slice;
@@ -9,10 +10,66 @@ use std::slice;
 /// Opaque handle to NURBSSurface (for Julia)
 pub struct NURBSSurfaceHandle {
     surface: Box<NURBSSurface>,
+    /// Interpret incoming (u, v) as normalized [0, 1] parameters
+    normalized: bool,
+}
+
+impl NURBSSurfaceHandle {
+    /// Map caller parameters to the knot domain
+    fn params(&self, u: f64, v: f64) -> (f64, f64) {
+        if self.normalized {
+            self.surface.normalized_to_domain(u, v)
+        } else {
+            (u, v)
+        }
+    }
+}
+
+/// Status codes reported by `nurbs_create_checked`
+pub const NURBS_OK: c_int = 0;
+pub const NURBS_ERR_INVALID_ARGUMENT: c_int = 1;
+pub const NURBS_ERR_INVALID_DIMENSION: c_int = 2;
+pub const NURBS_ERR_WEIGHT_SHAPE: c_int = 3;
+pub const NURBS_ERR_KNOT_LENGTH: c_int = 4;
+pub const NURBS_ERR_NON_MONOTONIC_KNOTS: c_int = 5;
+pub const NURBS_ERR_DEGENERATE_DOMAIN: c_int = 6;
+pub const NURBS_ERR_DEGREE_TOO_HIGH: c_int = 7;
+pub const NURBS_ERR_NON_POSITIVE_WEIGHT: c_int = 8;
+pub const NURBS_ERR_NON_FINITE: c_int = 9;
+
+/// Map a validation error to its FFI status code
+pub fn error_code(error: &NurbsError) -> c_int {
+    match error {
+        NurbsError::InvalidDimension { .. } => NURBS_ERR_INVALID_DIMENSION,
+        NurbsError::WeightShapeMismatch { .. } => NURBS_ERR_WEIGHT_SHAPE,
+        NurbsError::InvalidKnotLength { .. } => NURBS_ERR_KNOT_LENGTH,
+        NurbsError::NonMonotonicKnots { .. } => NURBS_ERR_NON_MONOTONIC_KNOTS,
+        NurbsError::DegenerateDomain { .. } => NURBS_ERR_DEGENERATE_DOMAIN,
+        NurbsError::DegreeTooHigh { .. } => NURBS_ERR_DEGREE_TOO_HIGH,
+        NurbsError::NonPositiveWeight { .. } => NURBS_ERR_NON_POSITIVE_WEIGHT,
+        NurbsError::NonFinite { .. } => NURBS_ERR_NON_FINITE,
+        // Fitting and construction errors do not arise from the FFI entry
+        // points; new variants must be mapped here explicitly
+        NurbsError::TooFewPoints { .. }
+        | NurbsError::CoincidentPoints { .. }
+        | NurbsError::SingularSystem
+        | NurbsError::LengthMismatch { .. }
+        | NurbsError::NonPositive { .. }
+        | NurbsError::ZeroVector { .. }
+        | NurbsError::InvalidRadius { .. }
+        | NurbsError::TooManyControlPoints { .. }
+        | NurbsError::ControlGridMismatch { .. }
+        | NurbsError::FixedPointOutOfRange { .. }
+        | NurbsError::ZeroDegree { .. }
+        | NurbsError::ParallelVectors { .. } => NURBS_ERR_INVALID_ARGUMENT,
+    }
 }
 
 /// Create NURBS surface from raw pointers
 ///
+/// Returns null if the inputs are invalid; use `nurbs_create_checked` to
+/// learn why.
+///
 /// # Safety
 /// Caller must ensure all pointers are valid and arrays have correct sizes
 #[no_mangle]
@@ -28,6 +85,58 @@ pub unsafe extern "C" fn nurbs_create(
     knots_u_len: c_int,
     knots_v_len: c_int,
 ) -> *mut NURBSSurfaceHandle {
+    nurbs_create_checked(
+        degree_u,
+        degree_v,
+        u_res,
+        v_res,
+        control_points,
+        weights,
+        knots_u,
+        knots_v,
+        knots_u_len,
+        knots_v_len,
+        std::ptr::null_mut(),
+    )
+}
+
+/// Create NURBS surface from raw pointers, reporting a status code
+///
+/// On failure returns null and writes one of the `NURBS_ERR_*` codes to
+/// `error_code` (if non-null); on success writes `NURBS_OK`.
+///
+/// # Safety
+/// Caller must ensure all pointers are valid and arrays have correct sizes
+#[no_mangle]
+pub unsafe extern "C" fn nurbs_create_checked(
+    degree_u: c_int,
+    degree_v: c_int,
+    u_res: c_int,
+    v_res: c_int,
+    control_points: *const c_double, // Flat array [u_res * v_res * 3]
+    weights: *const c_double,        // Flat array [u_res * v_res]
+    knots_u: *const c_double,
+    knots_v: *const c_double,
+    knots_u_len: c_int,
+    knots_v_len: c_int,
+    error_code_out: *mut c_int,
+) -> *mut NURBSSurfaceHandle {
+    let report = |code: c_int| {
+        if !error_code_out.is_null() {
+            *error_code_out = code;
+        }
+    };
+
+    if control_points.is_null() || weights.is_null() || knots_u.is_null() || knots_v.is_null() {
+        report(NURBS_ERR_INVALID_ARGUMENT);
+        return std::ptr::null_mut();
+    }
+
+    if degree_u < 0 || degree_v < 0 || u_res <= 0 || v_res <= 0 || knots_u_len < 0 || knots_v_len < 0 {
+        report(NURBS_ERR_INVALID_ARGUMENT);
+        return std::ptr::null_mut();
+    }
+
     let u_res = u_res as usize;
     let v_res = v_res as usize;
 
@@ -37,36 +146,70 @@ pub unsafe extern "C" fn nurbs_create(
     let knots_u_slice = slice::from_raw_parts(knots_u, knots_u_len as usize);
     let knots_v_slice = slice::from_raw_parts(knots_v, knots_v_len as usize);
 
-    // Build arrays
-    let control_points_array = match Array3::from_shape_vec(
-        (u_res, v_res, 3),
-        control_points_slice.to_vec(),
-    ) {
-        Ok(arr) => arr,
-        Err(_) => {
-            eprintln!("Error: Invalid control points shape");
-            return std::ptr::null_mut();
-        }
-    };
+    // Build arrays (shapes match the slice lengths by construction)
+    let control_points_array =
+        Array3::from_shape_vec((u_res, v_res, 3), control_points_slice.to_vec())
+            .expect("control point slice has u_res * v_res * 3 entries");
+    let weights_array = Array2::from_shape_vec((u_res, v_res), weights_slice.to_vec())
+        .expect("weight slice has u_res * v_res entries");
 
-    let weights_array = match Array2::from_shape_vec((u_res, v_res), weights_slice.to_vec()) {
-        Ok(arr) => arr,
-        Err(_) => {
-            eprintln!("Error: Invalid weights shape");
-            return std::ptr::null_mut();
-        }
-    };
-
-    let surface = Box::new(NURBSSurface::new(
+    match NURBSSurface::try_new(
         degree_u as usize,
         degree_v as usize,
         control_points_array,
         weights_array,
         knots_u_slice.to_vec(),
         knots_v_slice.to_vec(),
-    ));
+    ) {
+        Ok(surface) => {
+            report(NURBS_OK);
+            Box::into_raw(Box::new(NURBSSurfaceHandle {
+                surface: Box::new(surface),
+                normalized: false,
+            }))
+        }
+        Err(e) => {
+            report(error_code(&e));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Select how (u, v) arguments are interpreted
+///
+/// With `normalized` non-zero, parameters passed to the evaluation,
+/// normal and curvature functions are taken in [0, 1] and mapped onto the
+/// knot domain. By default they are knot-domain values.
+///
+/// # Safety
+/// Caller must ensure handle is valid
+#[no_mangle]
+pub unsafe extern "C" fn nurbs_set_normalized(handle: *mut NURBSSurfaceHandle, normalized: c_int) {
+    if handle.is_null() {
+        return;
+    }
 
-    Box::into_raw(Box::new(NURBSSurfaceHandle { surface }))
+    (*handle).normalized = normalized != 0;
+}
+
+/// Get the parameter domain of the surface
+///
+/// # Safety
+/// Caller must ensure handle and output are valid
+#[no_mangle]
+pub unsafe extern "C" fn nurbs_domain(
+    handle: *mut NURBSSurfaceHandle,
+    output: *mut c_double, // [u_min, u_max, v_min, v_max]
+) {
+    if handle.is_null() || output.is_null() {
+        return;
+    }
+
+    let handle = &*handle;
+    let ([u0, u1], [v0, v1]) = handle.surface.domain();
+
+    let output_slice = slice::from_raw_parts_mut(output, 4);
+    output_slice.copy_from_slice(&[u0, u1, v0, v1]);
 }
 
 /// Evaluate NURBS surface at single point
@@ -85,6 +228,7 @@ pub unsafe extern "C" fn nurbs_evaluate(
     }
 
     let handle = &*handle;
+    let (u, v) = handle.params(u, v);
     let point = handle.surface.evaluate(u, v);
 
     let output_slice = slice::from_raw_parts_mut(output, 3);
@@ -112,7 +256,10 @@ pub unsafe extern "C" fn nurbs_evaluate_batch(
 
     let uv_pairs: Vec<[f64; 2]> = uv_slice
         .chunks_exact(2)
-        .map(|chunk| [chunk[0], chunk[1]])
+        .map(|chunk| {
+            let (u, v) = handle.params(chunk[0], chunk[1]);
+            [u, v]
+        })
         .collect();
 
     let points = handle.surface.evaluate_batch(&uv_pairs);
@@ -122,7 +269,7 @@ pub unsafe extern "C" fn nurbs_evaluate_batch(
     }
 }
 
-/// Evaluate surface on uniform grid
+/// Evaluate surface on uniform grid spanning the knot domain
 ///
 /// # Safety
 /// Caller must ensure handle and output are valid and output has size [u_samples * v_samples * 3]
@@ -167,6 +314,7 @@ pub unsafe extern "C" fn nurbs_normal(
     }
 
     let handle = &*handle;
+    let (u, v) = handle.params(u, v);
     let normal = compute_normal(&handle.surface, u, v);
 
     let output_slice = slice::from_raw_parts_mut(output, 3);
@@ -189,6 +337,7 @@ pub unsafe extern "C" fn nurbs_curvature(
     }
 
     let handle = &*handle;
+    let (u, v) = handle.params(u, v);
     let (k1, k2) = compute_curvature(&handle.surface, u, v);
 
     let output_slice = slice::from_raw_parts_mut(output, 2);
@@ -196,6 +345,45 @@ pub unsafe extern "C" fn nurbs_curvature(
     output_slice[1] = k2;
 }
 
+/// Number of doubles written by `nurbs_curvature_info`
+pub const NURBS_CURVATURE_INFO_LEN: c_int = 20;
+
+/// Compute the full differential-geometry report at a point
+///
+/// Output layout (`NURBS_CURVATURE_INFO_LEN` doubles):
+/// [E, F, G, L, M, N, K, H, k1, k2, dir1 (3), dir2 (3), normal (3), umbilic]
+/// where `umbilic` is 1.0 or 0.0.
+///
+/// # Safety
+/// Caller must ensure handle and output are valid
+#[no_mangle]
+pub unsafe extern "C" fn nurbs_curvature_info(
+    handle: *mut NURBSSurfaceHandle,
+    u: c_double,
+    v: c_double,
+    output: *mut c_double,
+) {
+    if handle.is_null() || output.is_null() {
+        return;
+    }
+
+    let handle = &*handle;
+    let (u, v) = handle.params(u, v);
+    let info = compute_curvature_info(&handle.surface, u, v);
+
+    let output_slice = slice::from_raw_parts_mut(output, NURBS_CURVATURE_INFO_LEN as usize);
+    output_slice[0..3].copy_from_slice(&info.first_form);
+    output_slice[3..6].copy_from_slice(&info.second_form);
+    output_slice[6] = info.gaussian;
+    output_slice[7] = info.mean;
+    output_slice[8] = info.k1;
+    output_slice[9] = info.k2;
+    output_slice[10..13].copy_from_slice(&info.dir1);
+    output_slice[13..16].copy_from_slice(&info.dir2);
+    output_slice[16..19].copy_from_slice(&info.normal);
+    output_slice[19] = if info.umbilic { 1.0 } else { 0.0 };
+}
+
 /// Get surface dimensions
 ///
 /// # Safety
@@ -245,8 +433,8 @@ mod tests {
             1.0, 1.0, 0.0, // (1, 1)
         ];
 
-        let weights = vec![1.0, 1.0, 1.0, 1.0];
-        let knots = vec![0.0, 0.0, 1.0, 1.0];
+        let weights = [1.0, 1.0, 1.0, 1.0];
+        let knots = [0.0, 0.0, 1.0, 1.0];
 
         unsafe {
             let handle = nurbs_create(
@@ -274,4 +462,109 @@ mod tests {
             nurbs_free(handle);
         }
     }
+
+    #[test]
+    fn test_ffi_create_reports_error_code() {
+        let control_points = [0.0; 12];
+        let weights = [1.0, 1.0, -1.0, 1.0];
+        let knots = [0.0, 0.0, 1.0, 1.0];
+        let bad_knots = [0.0, 1.0, 0.5, 1.0];
+
+        unsafe {
+            let mut code = NURBS_OK;
+            let handle = nurbs_create_checked(
+                1,
+                1,
+                2,
+                2,
+                control_points.as_ptr(),
+                weights.as_ptr(),
+                knots.as_ptr(),
+                knots.as_ptr(),
+                4,
+                4,
+                &mut code,
+            );
+            assert!(handle.is_null());
+            assert_eq!(code, NURBS_ERR_NON_POSITIVE_WEIGHT);
+
+            let handle = nurbs_create_checked(
+                1,
+                1,
+                2,
+                2,
+                control_points.as_ptr(),
+                [1.0; 4].as_ptr(),
+                bad_knots.as_ptr(),
+                knots.as_ptr(),
+                4,
+                4,
+                &mut code,
+            );
+            assert!(handle.is_null());
+            assert_eq!(code, NURBS_ERR_NON_MONOTONIC_KNOTS);
+
+            // The unchecked entry point returns null instead of panicking
+            let handle = nurbs_create(
+                1,
+                1,
+                2,
+                2,
+                control_points.as_ptr(),
+                weights.as_ptr(),
+                knots.as_ptr(),
+                knots.as_ptr(),
+                3,
+                4,
+            );
+            assert!(handle.is_null());
+        }
+    }
+
+    #[test]
+    fn test_ffi_domain_and_normalized_parameters() {
+        // Bilinear square with knots on [0, 4] x [2, 3]
+        let control_points = [0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0];
+        let weights = [1.0; 4];
+        let knots_u = [0.0, 0.0, 4.0, 4.0];
+        let knots_v = [2.0, 2.0, 3.0, 3.0];
+
+        unsafe {
+            let handle = nurbs_create(
+                1,
+                1,
+                2,
+                2,
+                control_points.as_ptr(),
+                weights.as_ptr(),
+                knots_u.as_ptr(),
+                knots_v.as_ptr(),
+                4,
+                4,
+            );
+            assert!(!handle.is_null());
+
+            let mut domain = [0.0; 4];
+            nurbs_domain(handle, domain.as_mut_ptr());
+            assert_eq!(domain, [0.0, 4.0, 2.0, 3.0]);
+
+            let mut output = [0.0; 3];
+            nurbs_evaluate(handle, 1.0, 2.5, output.as_mut_ptr());
+            assert!((output[0] - 0.25).abs() < 1e-12);
+            assert!((output[1] - 0.5).abs() < 1e-12);
+
+            nurbs_set_normalized(handle, 1);
+            nurbs_evaluate(handle, 0.25, 0.5, output.as_mut_ptr());
+            assert!((output[0] - 0.25).abs() < 1e-12);
+            assert!((output[1] - 0.5).abs() < 1e-12);
+
+            // Flat patch: zero curvature, umbilic, normal along z
+            let mut info = [f64::NAN; NURBS_CURVATURE_INFO_LEN as usize];
+            nurbs_curvature_info(handle, 0.25, 0.5, info.as_mut_ptr());
+            assert_eq!(&info[6..10], &[0.0; 4]);
+            assert_eq!(&info[16..20], &[0.0, 0.0, 1.0, 1.0]);
+
+            nurbs_free(handle);
+        }
+    }
 }
@@ -0,0 +1,753 @@
+//! Fitting B-spline surfaces to data points
+//!
+//! Global interpolation follows the NURBS Book, section 9.2.5: parameters
+//! are averaged over the rows of the data grid, knots are placed by
+//! averaging (eq. 9.8), and the surface is found by solving one banded
+//! collocation system per direction for all rows at once.
+//!
+//! Least-squares approximation of scattered points solves the normal
+//! equations for a chosen control grid, optionally with a bending penalty on
+//! the control net and with some control points held fixed. In row-major
+//! control point order the normal matrix is banded, with a bandwidth of
+//! about p rows of the control grid, and is solved as such.
+
+use ndarray::{Array2, Array3, ArrayView2, Axis};
+
+use crate::basis::CoxDeBoor;
+use crate::error::NurbsError;
+use crate::linalg::BandMatrix;
+use crate::surface::NURBSSurface;
+use crate::vector;
+
+/// How data points are assigned parameter values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Parameterization {
+    /// Parameter steps proportional to the distance between points
+    #[default]
+    ChordLength,
+    /// Steps proportional to the square root of the distance, which
+    /// follows sharp turns more closely
+    Centripetal,
+}
+
+/// Settings for `NURBSSurface::approximate`
+#[derive(Debug, Clone)]
+pub struct ApproximationOptions {
+    pub degree_u: usize,
+    pub degree_v: usize,
+    /// Control grid size
+    pub control_u: usize,
+    pub control_v: usize,
+    /// Weight of the penalty on second differences of the control net
+    /// (0 for a pure least-squares fit)
+    pub smoothing: f64,
+    /// Control points held at given positions, as (i, j, point)
+    pub fixed: Vec<(usize, usize, [f64; 3])>,
+    /// Knot domain; defaults to the bounding box of the parameters
+    pub domain: Option<([f64; 2], [f64; 2])>,
+    /// Clamped knot vectors (u, v) to use instead of uniform knots over the
+    /// domain; their lengths must match the degrees and control grid size
+    pub knots: Option<(Vec<f64>, Vec<f64>)>,
+}
+
+impl ApproximationOptions {
+    /// Unsmoothed, unconstrained fit with the given degrees and grid size
+    pub fn new(degree_u: usize, degree_v: usize, control_u: usize, control_v: usize) -> Self {
+        Self {
+            degree_u,
+            degree_v,
+            control_u,
+            control_v,
+            smoothing: 0.0,
+            fixed: Vec::new(),
+            domain: None,
+            knots: None,
+        }
+    }
+
+    /// Hold the boundary control points at those of `surface`, which must
+    /// have the configured control grid size
+    pub fn with_fixed_boundary(mut self, surface: &NURBSSurface) -> Result<Self, NurbsError> {
+        let (nu, nv) = (self.control_u, self.control_v);
+        if surface.dimensions() != (nu, nv) {
+            return Err(NurbsError::ControlGridMismatch {
+                expected: (nu, nv),
+                found: surface.dimensions(),
+            });
+        }
+
+        for i in 0..nu {
+            for j in 0..nv {
+                if i == 0 || j == 0 || i == nu - 1 || j == nv - 1 {
+                    self.fixed.push((i, j, surface.control_point(i, j)));
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Result of a least-squares surface fit
+#[derive(Debug, Clone)]
+pub struct SurfaceFit {
+    pub surface: NURBSSurface,
+    /// Largest distance from a data point to the surface at its parameters
+    pub max_error: f64,
+    /// Root mean square of those distances
+    pub rms_error: f64,
+}
+
+impl NURBSSurface {
+    /// Least-squares fit of a non-rational surface to scattered points
+    ///
+    /// `params[k]` holds the (u, v) parameters of `points[k]`. Unless
+    /// `options.knots` is set, knots are clamped and uniform over
+    /// `options.domain`.
+    #[allow(clippy::needless_range_loop)]
+    pub fn approximate(
+        points: &[[f64; 3]],
+        params: &[[f64; 2]],
+        options: &ApproximationOptions,
+    ) -> Result<SurfaceFit, NurbsError> {
+        if params.len() != points.len() {
+            return Err(NurbsError::LengthMismatch {
+                field: "params",
+                expected: points.len(),
+                found: params.len(),
+            });
+        }
+        if points.iter().flatten().chain(params.iter().flatten()).any(|c| !c.is_finite()) {
+            return Err(NurbsError::NonFinite { field: "points" });
+        }
+
+        let (p, q) = (options.degree_u, options.degree_v);
+        let (nu, nv) = (options.control_u, options.control_v);
+        if nu < p + 1 {
+            return Err(NurbsError::DegreeTooHigh { axis: "u", degree: p, control_points: nu });
+        }
+        if nv < q + 1 {
+            return Err(NurbsError::DegreeTooHigh { axis: "v", degree: q, control_points: nv });
+        }
+
+        if let Some((knots_u, knots_v)) = &options.knots {
+            for (axis, knots, degree, count) in [("u", knots_u, p, nu), ("v", knots_v, q, nv)] {
+                if knots.len() != count + degree + 1 {
+                    return Err(NurbsError::InvalidKnotLength {
+                        axis,
+                        expected: count + degree + 1,
+                        found: knots.len(),
+                    });
+                }
+            }
+        }
+
+        let ([u0, u1], [v0, v1]) = match (&options.knots, options.domain) {
+            (Some((knots_u, knots_v)), _) => (
+                [knots_u[p], knots_u[knots_u.len() - p - 1]],
+                [knots_v[q], knots_v[knots_v.len() - q - 1]],
+            ),
+            (None, Some(domain)) => domain,
+            (None, None) => parameter_bounds(params).ok_or(NurbsError::CoincidentPoints { axis: "u" })?,
+        };
+        if u0 >= u1 {
+            return Err(NurbsError::CoincidentPoints { axis: "u" });
+        }
+        if v0 >= v1 {
+            return Err(NurbsError::CoincidentPoints { axis: "v" });
+        }
+
+        let (knots_u, knots_v) = match &options.knots {
+            Some(knots) => knots.clone(),
+            None => (uniform_knots(u0, u1, p, nu), uniform_knots(v0, v1, q, nv)),
+        };
+
+        // Normal equations G X = B over all nu * nv control points. Basis
+        // functions overlap within p rows and q columns of the control grid,
+        // and the bending penalty within two of either.
+        let n = nu * nv;
+        let bandwidth = (p * nv + q).max(2 * nv);
+        let mut g = BandMatrix::zeros(n, bandwidth, bandwidth);
+        let mut b = Array2::<f64>::zeros((n, 3));
+        let mut basis_u = vec![0.0; p + 1];
+        let mut basis_v = vec![0.0; q + 1];
+        let mut left = vec![0.0; p.max(q) + 1];
+        let mut right = vec![0.0; p.max(q) + 1];
+        let mut active = Vec::with_capacity((p + 1) * (q + 1));
+
+        for (point, &[u, v]) in points.iter().zip(params) {
+            let (u, v) = (u.clamp(u0, u1), v.clamp(v0, v1));
+            let span_u = CoxDeBoor::find_span(u, p, &knots_u);
+            let span_v = CoxDeBoor::find_span(v, q, &knots_v);
+            CoxDeBoor::basis_funs_with(span_u, u, p, &knots_u, &mut basis_u, &mut left, &mut right);
+            CoxDeBoor::basis_funs_with(span_v, v, q, &knots_v, &mut basis_v, &mut left, &mut right);
+
+            active.clear();
+            for a in 0..=p {
+                for c in 0..=q {
+                    let index = (span_u - p + a) * nv + span_v - q + c;
+                    active.push((index, basis_u[a] * basis_v[c]));
+                }
+            }
+
+            for &(r, nr) in &active {
+                for &(c, nc) in &active {
+                    g.add(r, c, nr * nc);
+                }
+                for d in 0..3 {
+                    b[[r, d]] += nr * point[d];
+                }
+            }
+        }
+
+        if options.smoothing > 0.0 {
+            add_bending_penalty(&mut g, nu, nv, options.smoothing);
+        }
+
+        // Move fixed control points to the right-hand side
+        let mut fixed = vec![None; n];
+        for &(i, j, point) in &options.fixed {
+            if i >= nu || j >= nv {
+                return Err(NurbsError::FixedPointOutOfRange { i, j });
+            }
+            fixed[i * nv + j] = Some(point);
+        }
+        let free: Vec<usize> = (0..n).filter(|&k| fixed[k].is_none()).collect();
+
+        // Dropping fixed rows and columns keeps the system inside the band
+        let mut free_index = vec![None; n];
+        for (r, &k) in free.iter().enumerate() {
+            free_index[k] = Some(r);
+        }
+
+        let mut g_free = BandMatrix::zeros(free.len(), bandwidth, bandwidth);
+        let mut solution = Array2::<f64>::zeros((free.len(), 3));
+        for (r, &k) in free.iter().enumerate() {
+            for d in 0..3 {
+                solution[[r, d]] = b[[k, d]];
+            }
+            for l in k.saturating_sub(bandwidth)..(k + bandwidth + 1).min(n) {
+                if let Some(c) = free_index[l] {
+                    g_free.set(r, c, g.get(k, l));
+                } else if let Some(point) = fixed[l] {
+                    for d in 0..3 {
+                        solution[[r, d]] -= g.get(k, l) * point[d];
+                    }
+                }
+            }
+        }
+
+        // The normal matrix is symmetric positive definite unless the data
+        // leave some control point undetermined
+        g_free.solve(&mut solution).ok_or(NurbsError::SingularSystem)?;
+        if solution.iter().any(|c| !c.is_finite()) {
+            return Err(NurbsError::SingularSystem);
+        }
+
+        let mut control_points = Array3::zeros((nu, nv, 3));
+        for (r, &k) in free.iter().enumerate() {
+            for d in 0..3 {
+                control_points[[k / nv, k % nv, d]] = solution[[r, d]];
+            }
+        }
+        for (k, point) in fixed.iter().enumerate() {
+            if let Some(point) = point {
+                for d in 0..3 {
+                    control_points[[k / nv, k % nv, d]] = point[d];
+                }
+            }
+        }
+
+        let surface = NURBSSurface::try_new(p, q, control_points, Array2::ones((nu, nv)), knots_u, knots_v)?;
+        Ok(fit_statistics(surface, points, params))
+    }
+
+    /// Least-squares fit to `points` parameterized by projection onto `self`
+    ///
+    /// Each point takes the parameters of its closest point on this surface,
+    /// and the fit uses this surface's domain unless `options.domain` is set.
+    pub fn approximate_projected(
+        &self,
+        points: &[[f64; 3]],
+        options: &ApproximationOptions,
+    ) -> Result<SurfaceFit, NurbsError> {
+        let params: Vec<[f64; 2]> = points
+            .iter()
+            .map(|&point| {
+                let closest = self.closest_point(point);
+                [closest.u, closest.v]
+            })
+            .collect();
+
+        let mut options = options.clone();
+        options.domain = options.domain.or(Some(self.domain()));
+        NURBSSurface::approximate(points, &params, &options)
+    }
+
+    /// Non-rational surface passing through a grid of points [n_u, n_v, 3]
+    ///
+    /// Point (k, l) is interpolated at parameters (u_k, v_l) in [0, 1]^2.
+    /// The control grid has the same size as the data grid.
+    pub fn interpolate(
+        points: &Array3<f64>,
+        degree_u: usize,
+        degree_v: usize,
+        method: Parameterization,
+    ) -> Result<NURBSSurface, NurbsError> {
+        let shape = points.shape();
+        if shape[2] != 3 {
+            return Err(NurbsError::InvalidDimension { expected: 3, found: shape[2] });
+        }
+        if shape[0] < degree_u + 1 {
+            return Err(NurbsError::TooFewPoints { axis: "u", degree: degree_u, points: shape[0] });
+        }
+        if shape[1] < degree_v + 1 {
+            return Err(NurbsError::TooFewPoints { axis: "v", degree: degree_v, points: shape[1] });
+        }
+        if points.iter().any(|c| !c.is_finite()) {
+            return Err(NurbsError::NonFinite { field: "points" });
+        }
+
+        let (n_u, n_v) = (shape[0], shape[1]);
+        let params_u = grid_parameters(points, Axis(0), method)?;
+        let params_v = grid_parameters(points, Axis(1), method)?;
+        let knots_u = averaged_knots(&params_u, degree_u);
+        let knots_v = averaged_knots(&params_v, degree_v);
+
+        // Interpolate along u: every v column is a right-hand side
+        let mut rows = points
+            .as_standard_layout()
+            .into_owned()
+            .into_shape((n_u, n_v * 3))
+            .expect("standard layout");
+        collocation_matrix(&params_u, &knots_u, degree_u)
+            .solve(&mut rows)
+            .ok_or(NurbsError::SingularSystem)?;
+
+        // Then along v through the intermediate control points
+        let mut columns = rows
+            .into_shape((n_u, n_v, 3))
+            .expect("contiguous grid")
+            .permuted_axes([1, 0, 2])
+            .as_standard_layout()
+            .into_owned()
+            .into_shape((n_v, n_u * 3))
+            .expect("standard layout");
+        collocation_matrix(&params_v, &knots_v, degree_v)
+            .solve(&mut columns)
+            .ok_or(NurbsError::SingularSystem)?;
+
+        let control_points = columns
+            .into_shape((n_v, n_u, 3))
+            .expect("contiguous grid")
+            .permuted_axes([1, 0, 2])
+            .as_standard_layout()
+            .into_owned();
+
+        NURBSSurface::try_new(
+            degree_u,
+            degree_v,
+            control_points,
+            Array2::ones((n_u, n_v)),
+            knots_u,
+            knots_v,
+        )
+    }
+}
+
+/// Parameters in [0, 1] for an ordered sequence of points (one per row)
+///
+/// Returns `None` if all points coincide.
+pub(crate) fn parameters(points: ArrayView2<f64>, method: Parameterization) -> Option<Vec<f64>> {
+    let n = points.shape()[0];
+    let steps: Vec<f64> = (1..n)
+        .map(|k| {
+            let d = (&points.row(k) - &points.row(k - 1)).mapv(|c| c * c).sum().sqrt();
+            match method {
+                Parameterization::ChordLength => d,
+                Parameterization::Centripetal => d.sqrt(),
+            }
+        })
+        .collect();
+
+    let total: f64 = steps.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut params = Vec::with_capacity(n);
+    params.push(0.0);
+    let mut acc = 0.0;
+    for step in &steps[..n - 2] {
+        acc += step;
+        params.push(acc / total);
+    }
+    params.push(1.0);
+
+    Some(params)
+}
+
+/// Parameters along `axis` of a point grid, averaged over the other axis
+///
+/// Rows whose points all coincide (e.g. at a pole) are skipped.
+pub(crate) fn grid_parameters(
+    points: &Array3<f64>,
+    axis: Axis,
+    method: Parameterization,
+) -> Result<Vec<f64>, NurbsError> {
+    let name = if axis == Axis(0) { "u" } else { "v" };
+    let other = Axis(1 - axis.index());
+    let n = points.len_of(axis);
+
+    let mut sum = vec![0.0; n];
+    let mut count = 0;
+    for line in points.axis_iter(other) {
+        if let Some(params) = parameters(line, method) {
+            for (s, t) in sum.iter_mut().zip(params) {
+                *s += t;
+            }
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return Err(NurbsError::CoincidentPoints { axis: name });
+    }
+
+    let mut params: Vec<f64> = sum.iter().map(|s| s / count as f64).collect();
+    params[n - 1] = 1.0;
+
+    if params.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(NurbsError::CoincidentPoints { axis: name });
+    }
+
+    Ok(params)
+}
+
+/// Clamped knot vector by averaging `degree` consecutive parameters
+/// (NURBS Book, eq. 9.8)
+pub(crate) fn averaged_knots(params: &[f64], degree: usize) -> Vec<f64> {
+    let n = params.len();
+    let mut knots = vec![params[0]; degree + 1];
+
+    for j in 1..n - degree {
+        let sum: f64 = params[j..j + degree].iter().sum();
+        knots.push(sum / degree as f64);
+    }
+
+    knots.extend(std::iter::repeat_n(params[n - 1], degree + 1));
+    knots
+}
+
+/// Clamped knot vector with uniform interior knots on [t0, t1]
+pub(crate) fn uniform_knots(t0: f64, t1: f64, degree: usize, control_points: usize) -> Vec<f64> {
+    let spans = control_points - degree;
+    let mut knots = vec![t0; degree + 1];
+    knots.extend((1..spans).map(|k| t0 + (t1 - t0) * k as f64 / spans as f64));
+    knots.extend(std::iter::repeat_n(t1, degree + 1));
+    knots
+}
+
+/// Bounding box of (u, v) parameters
+fn parameter_bounds(params: &[[f64; 2]]) -> Option<([f64; 2], [f64; 2])> {
+    let first = params.first()?;
+    let mut bounds = ([first[0]; 2], [first[1]; 2]);
+    for &[u, v] in params {
+        bounds.0 = [bounds.0[0].min(u), bounds.0[1].max(u)];
+        bounds.1 = [bounds.1[0].min(v), bounds.1[1].max(v)];
+    }
+    Some(bounds)
+}
+
+/// Add `weight` times the squared second differences of the control net,
+/// taken along both grid directions, to the normal matrix
+fn add_bending_penalty(g: &mut BandMatrix, nu: usize, nv: usize, weight: f64) {
+    let mut add_stencil = |indices: [usize; 3]| {
+        let coefficients = [1.0, -2.0, 1.0];
+        for (a, &r) in indices.iter().enumerate() {
+            for (b, &c) in indices.iter().enumerate() {
+                g.add(r, c, weight * coefficients[a] * coefficients[b]);
+            }
+        }
+    };
+
+    for i in 0..nu {
+        for j in 1..nv.saturating_sub(1) {
+            add_stencil([i * nv + j - 1, i * nv + j, i * nv + j + 1]);
+        }
+    }
+    for i in 1..nu.saturating_sub(1) {
+        for j in 0..nv {
+            add_stencil([(i - 1) * nv + j, i * nv + j, (i + 1) * nv + j]);
+        }
+    }
+}
+
+/// Wrap a fitted surface with its max and RMS error at the data points
+fn fit_statistics(surface: NURBSSurface, points: &[[f64; 3]], params: &[[f64; 2]]) -> SurfaceFit {
+    let distances: Vec<f64> = points
+        .iter()
+        .zip(params)
+        .map(|(point, &[u, v])| vector::distance(point, &surface.evaluate(u, v)))
+        .collect();
+
+    let max_error = distances.iter().cloned().fold(0.0, f64::max);
+    let rms_error = if distances.is_empty() {
+        0.0
+    } else {
+        (distances.iter().map(|d| d * d).sum::<f64>() / distances.len() as f64).sqrt()
+    };
+
+    SurfaceFit { surface, max_error, rms_error }
+}
+
+/// Banded matrix of basis function values N_i(params[k])
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn collocation_matrix(params: &[f64], knots: &[f64], degree: usize) -> BandMatrix {
+    let n = params.len();
+    let mut matrix = BandMatrix::zeros(n, degree, degree);
+    let mut basis = vec![0.0; degree + 1];
+    let (mut left, mut right) = (vec![0.0; degree + 1], vec![0.0; degree + 1]);
+
+    for (k, &t) in params.iter().enumerate() {
+        let span = CoxDeBoor::find_span(t, degree, knots);
+        CoxDeBoor::basis_funs_with(span, t, degree, knots, &mut basis, &mut left, &mut right);
+        for i in 0..=degree {
+            matrix.set(k, span - degree + i, basis[i]);
+        }
+    }
+
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn create_wavy_grid(n_u: usize, n_v: usize) -> Array3<f64> {
+        let mut points = Array3::zeros((n_u, n_v, 3));
+        for k in 0..n_u {
+            for l in 0..n_v {
+                // Uneven spacing in u to exercise the parameterization
+                let x = (k as f64 / (n_u - 1) as f64).powf(1.5) * 4.0;
+                let y = l as f64 * 0.8;
+                points[[k, l, 0]] = x;
+                points[[k, l, 1]] = y + 0.1 * x;
+                points[[k, l, 2]] = x.sin() * (0.5 * y).cos();
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_interpolation_passes_through_points() {
+        let points = create_wavy_grid(7, 6);
+
+        for method in [Parameterization::ChordLength, Parameterization::Centripetal] {
+            let surface = NURBSSurface::interpolate(&points, 3, 2, method).unwrap();
+            assert_eq!(surface.dimensions(), (7, 6));
+            assert_eq!(surface.domain(), ([0.0, 1.0], [0.0, 1.0]));
+
+            let params_u = grid_parameters(&points, Axis(0), method).unwrap();
+            let params_v = grid_parameters(&points, Axis(1), method).unwrap();
+            for (k, &u) in params_u.iter().enumerate() {
+                for (l, &v) in params_v.iter().enumerate() {
+                    let p = surface.evaluate(u, v);
+                    for d in 0..3 {
+                        assert_relative_eq!(p[d], points[[k, l, d]], epsilon = 1e-10);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Scattered parameters in [0, 1]^2 from a low-discrepancy sequence
+    fn scattered_params(count: usize) -> Vec<[f64; 2]> {
+        (0..count)
+            .map(|k| {
+                let u = (k as f64 * 0.618_033_988_75).fract();
+                let v = (k as f64 * 0.754_877_666_2).fract();
+                [u, v]
+            })
+            .collect()
+    }
+
+    fn create_target() -> NURBSSurface {
+        let (nu, nv) = (5, 6);
+        let mut control_points = Array3::zeros((nu, nv, 3));
+        for i in 0..nu {
+            for j in 0..nv {
+                control_points[[i, j, 0]] = i as f64;
+                control_points[[i, j, 1]] = j as f64;
+                control_points[[i, j, 2]] = ((i * j) as f64 * 0.4).sin();
+            }
+        }
+        NURBSSurface::new(
+            3,
+            2,
+            control_points,
+            Array2::ones((nu, nv)),
+            uniform_knots(0.0, 1.0, 3, nu),
+            uniform_knots(0.0, 1.0, 2, nv),
+        )
+    }
+
+    #[test]
+    fn test_approximation_reproduces_spline_data() {
+        let target = create_target();
+        let mut params = scattered_params(400);
+        params.extend([[0.0, 0.0], [1.0, 1.0]]);
+        let points: Vec<[f64; 3]> = params.iter().map(|&[u, v]| target.evaluate(u, v)).collect();
+
+        let options = ApproximationOptions::new(3, 2, 5, 6);
+        let fit = NURBSSurface::approximate(&points, &params, &options).unwrap();
+
+        assert!(fit.max_error < 1e-10, "max error {}", fit.max_error);
+        assert!(fit.rms_error <= fit.max_error);
+        for (a, b) in fit.surface.control_points.iter().zip(target.control_points.iter()) {
+            assert_relative_eq!(a, b, epsilon = 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_approximation_on_a_large_grid() {
+        // 1600 unknowns, most rows of the normal matrix far outside the band
+        let params = scattered_params(3000);
+        let points: Vec<[f64; 3]> = params
+            .iter()
+            .map(|&[u, v]| [u, v, (3.0 * u).sin() * (2.0 * v).cos()])
+            .collect();
+
+        let mut options = ApproximationOptions::new(3, 3, 40, 40);
+        options.smoothing = 1e-6;
+        let fit = NURBSSurface::approximate(&points, &params, &options).unwrap();
+        assert_eq!(fit.surface.dimensions(), (40, 40));
+        assert!(fit.max_error < 1e-3, "max error {}", fit.max_error);
+    }
+
+    #[test]
+    fn test_approximation_constraints_and_smoothing() {
+        let target = create_target();
+        let params = scattered_params(300);
+        let noisy: Vec<[f64; 3]> = params
+            .iter()
+            .enumerate()
+            .map(|(k, &[u, v])| {
+                let mut p = target.evaluate(u, v);
+                p[2] += 0.05 * ((k * 7919) % 13) as f64 / 13.0 - 0.025;
+                p
+            })
+            .collect();
+
+        // Fixed boundary control points are reproduced exactly
+        let options = ApproximationOptions::new(3, 2, 5, 6).with_fixed_boundary(&target).unwrap();
+        let fit = NURBSSurface::approximate(&noisy, &params, &options).unwrap();
+        for j in 0..6 {
+            assert_eq!(fit.surface.control_point(0, j), target.control_point(0, j));
+            assert_eq!(fit.surface.control_point(4, j), target.control_point(4, j));
+        }
+        assert!(fit.max_error < 0.1);
+
+        assert_eq!(
+            ApproximationOptions::new(3, 2, 6, 6).with_fixed_boundary(&target).unwrap_err(),
+            NurbsError::ControlGridMismatch { expected: (6, 6), found: (5, 6) }
+        );
+        let mut outside = ApproximationOptions::new(3, 2, 5, 6);
+        outside.fixed.push((5, 0, [0.0; 3]));
+        assert_eq!(
+            NURBSSurface::approximate(&noisy, &params, &outside).unwrap_err(),
+            NurbsError::FixedPointOutOfRange { i: 5, j: 0 }
+        );
+
+        // Smoothing trades data error for a straighter control net
+        let bending = |s: &NURBSSurface| -> f64 {
+            (1..4)
+                .flat_map(|i| (0..6).map(move |j| (i, j)))
+                .map(|(i, j)| {
+                    let a = s.control_point(i - 1, j)[2];
+                    let b = s.control_point(i, j)[2];
+                    let c = s.control_point(i + 1, j)[2];
+                    (a - 2.0 * b + c).powi(2)
+                })
+                .sum()
+        };
+        let options = ApproximationOptions::new(3, 2, 5, 6);
+        let rough = NURBSSurface::approximate(&noisy, &params, &options).unwrap();
+        let mut smooth_options = ApproximationOptions::new(3, 2, 5, 6);
+        smooth_options.smoothing = 10.0;
+        let smooth = NURBSSurface::approximate(&noisy, &params, &smooth_options).unwrap();
+        assert!(bending(&smooth.surface) < bending(&rough.surface));
+        assert!(smooth.rms_error > rough.rms_error);
+    }
+
+    #[test]
+    fn test_approximation_with_projected_parameters() {
+        let target = create_target();
+        let points: Vec<[f64; 3]> = scattered_params(200)
+            .iter()
+            .map(|&[u, v]| {
+                let mut p = target.evaluate(u, v);
+                p[2] += 0.01;
+                p
+            })
+            .collect();
+
+        // Project onto the flat base plane z = 0 of the same extent
+        let mut base_points = target.control_points.clone();
+        base_points.index_axis_mut(Axis(2), 2).fill(0.0);
+        let base = NURBSSurface::new(
+            3,
+            2,
+            base_points,
+            Array2::ones((5, 6)),
+            target.knots_u.clone(),
+            target.knots_v.clone(),
+        );
+
+        let fit = base.approximate_projected(&points, &ApproximationOptions::new(3, 2, 5, 6)).unwrap();
+        assert_eq!(fit.surface.domain(), base.domain());
+        assert!(fit.max_error < 1e-8, "max error {}", fit.max_error);
+
+        let options = ApproximationOptions::new(3, 2, 5, 6);
+        assert_eq!(
+            NURBSSurface::approximate(&points, &[[0.0, 0.0]], &options).unwrap_err(),
+            NurbsError::LengthMismatch { field: "params", expected: 200, found: 1 }
+        );
+    }
+
+    #[test]
+    fn test_averaged_knots() {
+        let params = [0.0, 0.1, 0.4, 0.6, 0.9, 1.0];
+        let knots = averaged_knots(&params, 3);
+
+        assert_eq!(knots.len(), params.len() + 3 + 1);
+        assert_relative_eq!(knots[4], (0.1 + 0.4 + 0.6) / 3.0, epsilon = 1e-15);
+        assert_relative_eq!(knots[5], (0.4 + 0.6 + 0.9) / 3.0, epsilon = 1e-15);
+        assert_eq!(&knots[..4], &[0.0; 4]);
+        assert_eq!(&knots[6..], &[1.0; 4]);
+    }
+
+    #[test]
+    fn test_interpolation_rejects_bad_grids() {
+        let points = create_wavy_grid(3, 6);
+        assert_eq!(
+            NURBSSurface::interpolate(&points, 3, 2, Parameterization::ChordLength).unwrap_err(),
+            NurbsError::TooFewPoints { axis: "u", degree: 3, points: 3 }
+        );
+
+        // Every u row collapses to a single point
+        let mut collapsed = create_wavy_grid(5, 4);
+        for k in 0..5 {
+            for d in 0..3 {
+                let value = collapsed[[0, 0, d]];
+                for l in 0..4 {
+                    collapsed[[k, l, d]] = if d == 1 { l as f64 } else { value };
+                }
+            }
+        }
+        assert_eq!(
+            NURBSSurface::interpolate(&collapsed, 2, 2, Parameterization::Centripetal).unwrap_err(),
+            NurbsError::CoincidentPoints { axis: "u" }
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Test surfaces shared by the unit tests of several modules
+
+use ndarray::{Array2, Array3};
+
+use crate::surface::NURBSSurface;
+
+/// Rational 5 x 4 patch of degree (3, 2) with one interior knot in each
+/// direction, on the unit square
+pub(crate) fn rational_patch() -> NURBSSurface {
+    let u_res = 5;
+    let v_res = 4;
+
+    let mut control_points = Array3::zeros((u_res, v_res, 3));
+    let mut weights = Array2::ones((u_res, v_res));
+    for i in 0..u_res {
+        for j in 0..v_res {
+            control_points[[i, j, 0]] = i as f64;
+            control_points[[i, j, 1]] = j as f64;
+            control_points[[i, j, 2]] = ((i * j) as f64).sin();
+            weights[[i, j]] = 1.0 + 0.25 * ((i + 2 * j) % 3) as f64;
+        }
+    }
+
+    let knots_u = vec![0.0, 0.0, 0.0, 0.0, 0.4, 1.0, 1.0, 1.0, 1.0];
+    let knots_v = vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0];
+
+    NURBSSurface::new(3, 2, control_points, weights, knots_u, knots_v)
+}
+
+/// Rational 7 x 6 patch of degree (3, 2) with three interior knots in u and
+/// three in v, on the unit square
+pub(crate) fn wavy_patch() -> NURBSSurface {
+    let u_res = 7;
+    let v_res = 6;
+
+    let mut control_points = Array3::zeros((u_res, v_res, 3));
+    let mut weights = Array2::ones((u_res, v_res));
+    for i in 0..u_res {
+        for j in 0..v_res {
+            control_points[[i, j, 0]] = i as f64;
+            control_points[[i, j, 1]] = j as f64;
+            control_points[[i, j, 2]] = (i as f64 * 1.3).sin() + (j as f64 * 0.7).cos();
+            weights[[i, j]] = 1.0 + 0.2 * ((i + j) % 2) as f64;
+        }
+    }
+
+    let knots_u = vec![0.0, 0.0, 0.0, 0.0, 0.25, 0.5, 0.75, 1.0, 1.0, 1.0, 1.0];
+    let knots_v = vec![0.0, 0.0, 0.0, 0.3, 0.5, 0.7, 1.0, 1.0, 1.0];
+
+    NURBSSurface::new(3, 2, control_points, weights, knots_u, knots_v)
+}
@@ -0,0 +1,185 @@
+//! Surface integrals by Gauss–Legendre quadrature
+//!
+//! Integrals over the surface are taken span by span in parameter space,
+//! where dA = |S_u x S_v| du dv = sqrt(EG - F^2) du dv. The integrand is
+//! smooth inside each knot span, so a tensor-product Gauss–Legendre rule
+//! per span converges quickly; it is exact only for polynomial surfaces,
+//! as rational parameterizations make the area element irrational.
+
+use rayon::prelude::*;
+
+use crate::bezier::breakpoints;
+use crate::surface::NURBSSurface;
+use crate::vector::{cross, norm};
+
+/// Gauss points per knot span and direction used by `area` and `centroid`
+pub const DEFAULT_ORDER: usize = 10;
+
+impl NURBSSurface {
+    /// Surface area
+    pub fn area(&self) -> f64 {
+        self.integrate(DEFAULT_ORDER, |_, _, _| 1.0)
+    }
+
+    /// Area-weighted mean of the surface points
+    ///
+    /// Returns `None` for surfaces of zero area.
+    pub fn centroid(&self) -> Option<[f64; 3]> {
+        let [x, y, z, area] = self.quadrature(DEFAULT_ORDER, |_, _, p| [p[0], p[1], p[2], 1.0]);
+        (area > 0.0).then(|| [x / area, y / area, z / area])
+    }
+
+    /// Integral of f(u, v, S(u, v)) over the surface, with `order` Gauss
+    /// points per knot span in each direction
+    ///
+    /// # Panics
+    /// Panics if `order` is zero.
+    pub fn integrate<F>(&self, order: usize, f: F) -> f64
+    where
+        F: Fn(f64, f64, [f64; 3]) -> f64 + Sync,
+    {
+        self.quadrature(order, |u, v, p| [f(u, v, p)])[0]
+    }
+
+    /// Integrals of the components of a vector-valued integrand
+    fn quadrature<const N: usize, F>(&self, order: usize, f: F) -> [f64; N]
+    where
+        F: Fn(f64, f64, [f64; 3]) -> [f64; N] + Sync,
+    {
+        assert!(order > 0, "quadrature needs at least one point");
+        let (nodes, weights) = gauss_legendre(order);
+        let us = breakpoints(&self.knots_u, self.degree_u);
+        let vs = breakpoints(&self.knots_v, self.degree_v);
+
+        // Map the rule onto each span; the span width enters the weights
+        let span_rule = |a: f64, b: f64| -> Vec<(f64, f64)> {
+            let (mid, half) = (0.5 * (a + b), 0.5 * (b - a));
+            nodes.iter().zip(&weights).map(|(&x, &w)| (mid + half * x, half * w)).collect()
+        };
+
+        us.par_windows(2)
+            .map(|span_u| {
+                let rule_u = span_rule(span_u[0], span_u[1]);
+                let mut sum = [0.0; N];
+                for span_v in vs.windows(2) {
+                    for &(v, wv) in &span_rule(span_v[0], span_v[1]) {
+                        for &(u, wu) in &rule_u {
+                            let ders = self.derivatives(u, v, 1);
+                            let jacobian = norm(&cross(&ders[1][0], &ders[0][1]));
+                            let values = f(u, v, ders[0][0]);
+                            for k in 0..N {
+                                sum[k] += wu * wv * jacobian * values[k];
+                            }
+                        }
+                    }
+                }
+                sum
+            })
+            .reduce(|| [0.0; N], |a, b| std::array::from_fn(|k| a[k] + b[k]))
+    }
+}
+
+/// Nodes and weights of the n-point Gauss–Legendre rule on [-1, 1]
+///
+/// Roots of P_n by Newton iteration from Chebyshev-like initial guesses,
+/// with weights 2 / ((1 - x^2) P_n'(x)^2).
+pub(crate) fn gauss_legendre(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut nodes = vec![0.0; n];
+    let mut weights = vec![0.0; n];
+
+    for i in 0..n.div_ceil(2) {
+        let mut x = (std::f64::consts::PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+        let mut derivative = 0.0;
+        for _ in 0..100 {
+            // P_n(x) and P_n'(x) by the three-term recurrence
+            let (mut p0, mut p1) = (1.0, x);
+            for k in 2..=n {
+                let p2 = ((2 * k - 1) as f64 * x * p1 - (k - 1) as f64 * p0) / k as f64;
+                p0 = p1;
+                p1 = p2;
+            }
+            derivative = n as f64 * (x * p1 - p0) / (x * x - 1.0);
+
+            let step = p1 / derivative;
+            x -= step;
+            if step.abs() <= 1e-15 {
+                break;
+            }
+        }
+
+        let weight = 2.0 / ((1.0 - x * x) * derivative * derivative);
+        nodes[i] = -x;
+        nodes[n - 1 - i] = x;
+        weights[i] = weight;
+        weights[n - 1 - i] = weight;
+    }
+    (nodes, weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_gauss_legendre_rules() {
+        let (nodes, weights) = gauss_legendre(1);
+        assert_relative_eq!(nodes[0], 0.0, epsilon = 1e-15);
+        assert_relative_eq!(weights[0], 2.0, epsilon = 1e-15);
+
+        let (nodes, weights) = gauss_legendre(3);
+        assert_relative_eq!(nodes[2], 0.6f64.sqrt(), epsilon = 1e-15);
+        assert_relative_eq!(weights[1], 8.0 / 9.0, epsilon = 1e-15);
+
+        // n points integrate polynomials up to degree 2n - 1 exactly
+        for n in [4, 7, 10] {
+            let (nodes, weights) = gauss_legendre(n);
+            for degree in 0..2 * n {
+                let sum: f64 = nodes.iter().zip(&weights).map(|(x, w)| w * x.powi(degree as i32)).sum();
+                let exact = if degree % 2 == 1 { 0.0 } else { 2.0 / (degree + 1) as f64 };
+                assert_relative_eq!(sum, exact, epsilon = 1e-13);
+            }
+        }
+    }
+
+    #[test]
+    fn test_primitive_areas() {
+        let plane = NURBSSurface::plane([1.0, 2.0, 3.0], [2.0, 0.0, 0.0], [1.0, 3.0, 0.0]);
+        assert_relative_eq!(plane.area(), 6.0, epsilon = 1e-13);
+
+        let sphere = NURBSSurface::sphere([0.5, 0.0, -1.0], 2.0);
+        assert_relative_eq!(sphere.area(), 16.0 * PI, max_relative = 1e-9);
+
+        let cylinder = NURBSSurface::cylinder([0.0; 3], [1.0, 2.0, 2.0], 1.5, 4.0);
+        assert_relative_eq!(cylinder.area(), 2.0 * PI * 1.5 * 4.0, max_relative = 1e-9);
+
+        let cone = NURBSSurface::cone([0.0; 3], [0.0, 0.0, 1.0], 2.0, 1.0, 3.0);
+        let slant = (1.0f64 + 9.0).sqrt();
+        assert_relative_eq!(cone.area(), PI * (2.0 + 1.0) * slant, max_relative = 1e-9);
+
+        let torus = NURBSSurface::torus([0.0; 3], [0.0, 1.0, 0.0], 3.0, 0.5);
+        assert_relative_eq!(torus.area(), 4.0 * PI * PI * 3.0 * 0.5, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_scalar_fields_and_centroid() {
+        // Over the unit sphere, the integral of z^2 is 4 pi / 3
+        let sphere = NURBSSurface::sphere([0.0; 3], 1.0);
+        let integral = sphere.integrate(DEFAULT_ORDER, |_, _, p| p[2] * p[2]);
+        assert_relative_eq!(integral, 4.0 * PI / 3.0, max_relative = 1e-9);
+
+        // v < 0.5 is the lower hemisphere
+        let lower = sphere.integrate(DEFAULT_ORDER, |_, v, _| if v < 0.5 { 1.0 } else { 0.0 });
+        assert_relative_eq!(lower, 2.0 * PI, max_relative = 1e-9);
+
+        let centroid = NURBSSurface::sphere([1.0, -2.0, 0.5], 3.0).centroid().unwrap();
+        for (c, expected) in centroid.iter().zip([1.0, -2.0, 0.5]) {
+            assert_relative_eq!(*c, expected, epsilon = 1e-10);
+        }
+
+        // Lateral surface of a cone: the centroid sits at h (r1 + 2 r2) / (3 (r1 + r2))
+        let cone = NURBSSurface::cone([0.0; 3], [0.0, 0.0, 1.0], 2.0, 1.0, 3.0);
+        assert_relative_eq!(cone.centroid().unwrap()[2], 3.0 * 4.0 / 9.0, max_relative = 1e-9);
+    }
+}
@@ -0,0 +1,254 @@
+//! Ray–surface intersection
+//!
+//! The surface is decomposed into Bezier patches, which are subdivided
+//! while the ray passes through their control-point boxes and they are not
+//! yet flat. Each flat sub-patch seeds a Newton iteration on
+//! S(u, v) - (o + t d) = 0 with analytic derivatives. Hits found from
+//! neighbouring sub-patches are merged.
+
+use crate::bounds::Bvh;
+use crate::derivatives::compute_normal;
+use crate::surface::NURBSSurface;
+use crate::vector::{add, cross, distance, dot, norm, scale, sub};
+
+/// Deviation from the bilinear corner patch, relative to the size of the
+/// whole patch, below which a sub-patch seeds Newton iteration
+const FLATNESS: f64 = 1e-2;
+
+/// Subdivision depth limit, reached only near tangential hits
+const MAX_DEPTH: usize = 12;
+
+const MAX_ITERATIONS: usize = 30;
+
+/// Relative tolerance on the residual and on merging duplicate hits
+const TOLERANCE: f64 = 1e-10;
+
+/// Intersection of a ray with a surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub u: f64,
+    pub v: f64,
+    /// Ray parameter: the hit is at origin + t direction
+    pub t: f64,
+    pub point: [f64; 3],
+    /// Unit normal S_u x S_v
+    pub normal: [f64; 3],
+}
+
+impl NURBSSurface {
+    /// All intersections of the ray origin + t direction (t >= 0), ordered
+    /// by t
+    ///
+    /// Tangential contacts are found as long as Newton iteration converges
+    /// there; hits at degenerate points of the surface (where S_u x S_v
+    /// vanishes) may be missed.
+    pub fn intersect_ray(&self, origin: [f64; 3], direction: [f64; 3]) -> Vec<RayHit> {
+        let mut hits = Vec::new();
+        for patch in self.to_bezier_patches().into_iter().flatten() {
+            patch.surface.patch_hits(origin, direction, &mut hits);
+        }
+        finish(hits)
+    }
+
+    /// Collect hits on a Bezier patch into `hits`, unsorted and unmerged
+    fn patch_hits(&self, origin: [f64; 3], direction: [f64; 3], hits: &mut Vec<RayHit>) {
+        let size = norm(&self.bounds().size());
+        let mut stack = vec![(self.clone(), 0)];
+
+        while let Some((patch, depth)) = stack.pop() {
+            if patch.bounds().expand(TOLERANCE * size).ray_interval(origin, direction).is_none() {
+                continue;
+            }
+
+            if depth < MAX_DEPTH && bilinear_deviation(&patch) > FLATNESS * size {
+                let ([u0, u1], [v0, v1]) = patch.domain();
+                let (left, right) = patch.split_u(0.5 * (u0 + u1));
+                for half in [left, right] {
+                    let (bottom, top) = half.split_v(0.5 * (v0 + v1));
+                    stack.push((bottom, depth + 1));
+                    stack.push((top, depth + 1));
+                }
+                continue;
+            }
+
+            if let Some(hit) = self.newton_hit(&patch, origin, direction, size) {
+                hits.push(hit);
+            }
+        }
+    }
+
+    /// Newton iteration from the center of `patch`, accepted if it
+    /// converges inside it
+    fn newton_hit(
+        &self,
+        patch: &NURBSSurface,
+        origin: [f64; 3],
+        direction: [f64; 3],
+        size: f64,
+    ) -> Option<RayHit> {
+        let ([u0, u1], [v0, v1]) = patch.domain();
+        let ([a0, a1], [b0, b1]) = self.domain();
+        let (mut u, mut v) = (0.5 * (u0 + u1), 0.5 * (v0 + v1));
+        let mut t = dot(&sub(&self.evaluate(u, v), &origin), &direction) / dot(&direction, &direction);
+
+        for _ in 0..MAX_ITERATIONS {
+            let ders = self.derivatives(u, v, 1);
+            let residual = sub(&ders[0][0], &add(&origin, &scale(&direction, t)));
+            if norm(&residual) <= TOLERANCE * size {
+                let slack = TOLERANCE * (u1 - u0 + v1 - v0);
+                let inside = u >= u0 - slack && u <= u1 + slack && v >= v0 - slack && v <= v1 + slack;
+                return (inside && t >= 0.0).then(|| RayHit {
+                    u,
+                    v,
+                    t,
+                    point: ders[0][0],
+                    normal: compute_normal(self, u, v),
+                });
+            }
+
+            // Solve [S_u S_v -d] (du, dv, dt) = -residual by Cramer's rule
+            let (su, sv, nd) = (ders[1][0], ders[0][1], scale(&direction, -1.0));
+            let det = dot(&su, &cross(&sv, &nd));
+            if det.abs() < f64::MIN_POSITIVE {
+                return None;
+            }
+            let r = scale(&residual, -1.0);
+            u = (u + dot(&r, &cross(&sv, &nd)) / det).clamp(a0, a1);
+            v = (v + dot(&su, &cross(&r, &nd)) / det).clamp(b0, b1);
+            t += dot(&su, &cross(&sv, &r)) / det;
+        }
+        None
+    }
+}
+
+impl Bvh {
+    /// All intersections of the ray with the surfaces of the hierarchy, as
+    /// (surface index, hit) ordered by t
+    pub fn intersect_ray(&self, origin: [f64; 3], direction: [f64; 3]) -> Vec<(usize, RayHit)> {
+        let mut hits: Vec<(usize, RayHit)> = Vec::new();
+        for (index, _) in self.ray_candidates(origin, direction) {
+            let item = &self.items()[index];
+            let mut patch_hits = Vec::new();
+            item.patch.surface.patch_hits(origin, direction, &mut patch_hits);
+            hits.extend(finish(patch_hits).into_iter().map(|hit| (item.surface, hit)));
+        }
+
+        // Hits on shared patch edges are found from both sides
+        hits.sort_by(|a, b| a.1.t.total_cmp(&b.1.t));
+        hits.dedup_by(|a, b| a.0 == b.0 && is_duplicate(&a.1, &b.1));
+        hits
+    }
+}
+
+/// Sort hits by t and merge those found more than once
+fn finish(mut hits: Vec<RayHit>) -> Vec<RayHit> {
+    hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+    hits.dedup_by(|a, b| is_duplicate(a, b));
+    hits
+}
+
+fn is_duplicate(a: &RayHit, b: &RayHit) -> bool {
+    distance(&a.point, &b.point) <= 1e3 * TOLERANCE * (1.0 + norm(&a.point))
+}
+
+/// Largest distance of a Bezier patch's control points from the bilinear
+/// patch through its corner control points
+pub(crate) fn bilinear_deviation(patch: &NURBSSurface) -> f64 {
+    let (n_u, n_v) = patch.dimensions();
+    let corners = [
+        patch.control_point(0, 0),
+        patch.control_point(n_u - 1, 0),
+        patch.control_point(0, n_v - 1),
+        patch.control_point(n_u - 1, n_v - 1),
+    ];
+
+    let mut deviation: f64 = 0.0;
+    for i in 0..n_u {
+        for j in 0..n_v {
+            let (a, b) = (i as f64 / (n_u - 1) as f64, j as f64 / (n_v - 1) as f64);
+            let weights = [(1.0 - a) * (1.0 - b), a * (1.0 - b), (1.0 - a) * b, a * b];
+            let mut bilinear = [0.0; 3];
+            for (corner, w) in corners.iter().zip(weights) {
+                for d in 0..3 {
+                    bilinear[d] += w * corner[d];
+                }
+            }
+            deviation = deviation.max(distance(&patch.control_point(i, j), &bilinear));
+        }
+    }
+    deviation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_ray_through_sphere() {
+        let center = [1.0, 2.0, -1.0];
+        let sphere = NURBSSurface::sphere(center, 2.0);
+
+        // Through the center from 5 away: enters at t = 3, leaves at t = 7
+        let hits = sphere.intersect_ray([6.0, 2.0, -1.0], [-1.0, 0.0, 0.0]);
+        assert_eq!(hits.len(), 2);
+        assert_relative_eq!(hits[0].t, 3.0, epsilon = 1e-9);
+        assert_relative_eq!(hits[1].t, 7.0, epsilon = 1e-9);
+        assert_relative_eq!(hits[0].normal[0], 1.0, epsilon = 1e-9);
+        assert_relative_eq!(hits[1].normal[0], -1.0, epsilon = 1e-9);
+
+        // Off-center at distance 1.5 from the center, with a scaled direction
+        let hits = sphere.intersect_ray([1.0, 2.0 + 1.5, 4.0], [0.0, 0.0, -2.0]);
+        let half_chord = (4.0f64 - 2.25).sqrt();
+        assert_eq!(hits.len(), 2);
+        assert_relative_eq!(hits[0].t, (5.0 - half_chord) / 2.0, epsilon = 1e-9);
+        assert_relative_eq!(hits[1].t, (5.0 + half_chord) / 2.0, epsilon = 1e-9);
+        for hit in &hits {
+            assert_relative_eq!(distance(&hit.point, &center), 2.0, epsilon = 1e-9);
+            let expected = sphere.evaluate(hit.u, hit.v);
+            assert_relative_eq!(distance(&hit.point, &expected), 0.0, epsilon = 1e-9);
+        }
+
+        assert!(sphere.intersect_ray([1.0, 4.5, 4.0], [0.0, 0.0, -1.0]).is_empty());
+        assert!(sphere.intersect_ray([6.0, 2.0, -1.0], [1.0, 0.0, 0.0]).is_empty());
+    }
+
+    #[test]
+    fn test_ray_from_inside_cylinder_and_through_torus() {
+        let cylinder = NURBSSurface::cylinder([0.0; 3], [0.0, 0.0, 1.0], 1.0, 2.0);
+        let hits = cylinder.intersect_ray([0.2, 0.0, 1.0], [0.6, 0.8, 0.0]);
+        assert_eq!(hits.len(), 1);
+        let p = hits[0].point;
+        assert_relative_eq!(p[0].hypot(p[1]), 1.0, epsilon = 1e-9);
+        assert_relative_eq!(p[2], 1.0, epsilon = 1e-9);
+
+        // In the equatorial plane through the center: four crossings
+        let torus = NURBSSurface::torus([0.0; 3], [0.0, 0.0, 1.0], 3.0, 1.0);
+        let hits = torus.intersect_ray([-10.0, 0.0, 0.0], [1.0, 0.0, 0.0]);
+        let xs: Vec<f64> = hits.iter().map(|hit| hit.point[0]).collect();
+        assert_eq!(xs.len(), 4);
+        for (x, expected) in xs.iter().zip([-4.0, -2.0, 2.0, 4.0]) {
+            assert_relative_eq!(*x, expected, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_bvh_picking() {
+        let surfaces = vec![
+            NURBSSurface::sphere([0.0, 0.0, 0.0], 1.0),
+            NURBSSurface::sphere([0.0, 0.0, 4.0], 1.0),
+            NURBSSurface::plane([-5.0, -5.0, 8.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]),
+        ];
+        let bvh = Bvh::new(&surfaces);
+
+        let hits = bvh.intersect_ray([0.3, 0.2, -3.0], [0.0, 0.0, 1.0]);
+        let order: Vec<usize> = hits.iter().map(|(surface, _)| *surface).collect();
+        assert_eq!(order, vec![0, 0, 1, 1, 2]);
+        assert_relative_eq!(hits[4].1.t, 11.0, epsilon = 1e-9);
+        assert_relative_eq!(hits[4].1.u, 0.53, epsilon = 1e-9);
+
+        // Each hit agrees with intersecting the surface alone
+        let alone = surfaces[1].intersect_ray([0.3, 0.2, -3.0], [0.0, 0.0, 1.0]);
+        assert_relative_eq!(alone[0].t, hits[2].1.t, epsilon = 1e-12);
+    }
+}
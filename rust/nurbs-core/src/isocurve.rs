@@ -0,0 +1,114 @@
+//! Isoparametric curves
+//!
+//! Fixing one parameter blends the rows of homogeneous control points with
+//! the basis functions of that direction, which yields the exact rational
+//! curve in the other direction.
+
+use ndarray::Array2;
+
+use crate::basis::CoxDeBoor;
+use crate::curve::NURBSCurve;
+use crate::surface::{Direction, NURBSSurface};
+
+impl NURBSSurface {
+    /// Curve C(v) = S(u, v) at fixed `u`
+    ///
+    /// The curve has degree q and the v knot vector of the surface.
+    pub fn iso_curve_u(&self, u: f64) -> NURBSCurve {
+        self.iso_curve(Direction::U, u)
+    }
+
+    /// Curve C(u) = S(u, v) at fixed `v`
+    ///
+    /// The curve has degree p and the u knot vector of the surface.
+    pub fn iso_curve_v(&self, v: f64) -> NURBSCurve {
+        self.iso_curve(Direction::V, v)
+    }
+
+    fn iso_curve(&self, fixed: Direction, t: f64) -> NURBSCurve {
+        let (p, knots) = self.basis_in(fixed);
+        let span = CoxDeBoor::find_span(t, p, knots);
+        let mut basis = vec![0.0; p + 1];
+        let (mut left, mut right) = (vec![0.0; p + 1], vec![0.0; p + 1]);
+        CoxDeBoor::basis_funs_with(span, t, p, knots, &mut basis, &mut left, &mut right);
+
+        let pw = self.homogeneous();
+        let (u_res, v_res) = self.dimensions();
+        let len = match fixed {
+            Direction::U => v_res,
+            Direction::V => u_res,
+        };
+
+        let mut qw = Array2::zeros((len, 4));
+        for k in 0..len {
+            for (a, &n) in basis.iter().enumerate() {
+                let i = span - p + a;
+                for d in 0..4 {
+                    qw[[k, d]] += n * match fixed {
+                        Direction::U => pw[[i, k, d]],
+                        Direction::V => pw[[k, i, d]],
+                    };
+                }
+            }
+        }
+
+        match fixed {
+            Direction::U => NURBSCurve::from_homogeneous(self.degree_v, &qw, self.knots_v.clone()),
+            Direction::V => NURBSCurve::from_homogeneous(self.degree_u, &qw, self.knots_u.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::rational_patch;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_iso_curves_lie_on_surface() {
+        let surface = rational_patch();
+
+        for &u in &[0.0, 0.3, 0.4, 1.0] {
+            let curve = surface.iso_curve_u(u);
+            assert_eq!(curve.degree, 2);
+            assert_eq!(curve.knots, surface.knots_v);
+
+            for k in 0..=10 {
+                let v = k as f64 / 10.0;
+                let expected = surface.evaluate(u, v);
+                let actual = curve.evaluate(v);
+                for d in 0..3 {
+                    assert_relative_eq!(expected[d], actual[d], epsilon = 1e-12);
+                }
+            }
+        }
+
+        for &v in &[0.0, 0.25, 1.0] {
+            let curve = surface.iso_curve_v(v);
+            assert_eq!(curve.degree, 3);
+
+            for k in 0..=10 {
+                let u = k as f64 / 10.0;
+                let expected = surface.evaluate(u, v);
+                let actual = curve.evaluate(u);
+                for d in 0..3 {
+                    assert_relative_eq!(expected[d], actual[d], epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_boundary_iso_curve_matches_control_row() {
+        let surface = rational_patch();
+
+        // Clamped boundary: the isocurve is the boundary row itself
+        let curve = surface.iso_curve_u(1.0);
+        for j in 0..4 {
+            assert_relative_eq!(curve.weight(j), surface.weight(4, j), epsilon = 1e-14);
+            for (a, b) in curve.control_point(j).iter().zip(surface.control_point(4, j)) {
+                assert_relative_eq!(*a, b, epsilon = 1e-14);
+            }
+        }
+    }
+}
@@ -2,13 +2,47 @@
 //! Exposes C-compatible FFI for Julia interop
 
 pub mod basis;
+pub mod error;
 pub mod surface;
+pub mod curve;
 pub mod derivatives;
+pub mod refine;
+pub mod elevate;
+pub mod removal;
+pub mod bezier;
+pub mod projection;
+pub mod split;
+pub mod isocurve;
+pub mod fitting;
+pub mod curve_fitting;
+pub mod construct;
+pub mod primitives;
+pub mod offset;
+pub mod bounds;
+pub mod intersect;
+pub mod ssi;
+pub mod section;
+pub mod integrate;
 pub mod ffi;
 
+mod linalg;
+mod vector;
+
+#[cfg(test)]
+mod fixtures;
+
 pub use basis::CoxDeBoor;
+pub use error::NurbsError;
+pub use fitting::{ApproximationOptions, Parameterization, SurfaceFit};
 pub use surface::NURBSSurface;
-pub use derivatives::{compute_tangent, compute_normal, compute_curvature};
+pub use curve::NURBSCurve;
+pub use curve_fitting::CurveFit;
+pub use offset::{OffsetHazard, OffsetSurface};
+pub use bounds::{Aabb, Bvh, BvhItem};
+pub use intersect::RayHit;
+pub use ssi::{IntersectionBranch, IntersectionOptions};
+pub use section::{Contour, Slice};
+pub use derivatives::{compute_tangent, compute_normal, compute_curvature, compute_curvature_info, CurvatureInfo};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,138 @@
+//! Banded linear solver for spline collocation systems
+//!
+//! B-spline collocation matrices are totally positive, so Gaussian
+//! elimination without pivoting is stable (NURBS Book, section 9.2.1) and
+//! keeps the fill-in inside the band.
+
+use ndarray::Array2;
+
+/// Square matrix storing only the diagonals from -lower to +upper
+#[derive(Debug, Clone)]
+pub(crate) struct BandMatrix {
+    n: usize,
+    lower: usize,
+    upper: usize,
+    data: Vec<f64>,
+}
+
+impl BandMatrix {
+    pub(crate) fn zeros(n: usize, lower: usize, upper: usize) -> Self {
+        Self {
+            n,
+            lower,
+            upper,
+            data: vec![0.0; n * (lower + upper + 1)],
+        }
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        debug_assert!(j + self.lower >= i && j <= i + self.upper, "({}, {}) outside the band", i, j);
+        i * (self.lower + self.upper + 1) + j + self.lower - i
+    }
+
+    pub(crate) fn get(&self, i: usize, j: usize) -> f64 {
+        if j + self.lower < i || j > i + self.upper {
+            0.0
+        } else {
+            self.data[self.index(i, j)]
+        }
+    }
+
+    pub(crate) fn set(&mut self, i: usize, j: usize, value: f64) {
+        let idx = self.index(i, j);
+        self.data[idx] = value;
+    }
+
+    pub(crate) fn add(&mut self, i: usize, j: usize, value: f64) {
+        let idx = self.index(i, j);
+        self.data[idx] += value;
+    }
+
+    /// Solve A X = B in place for every column of `rhs` ([n, k])
+    ///
+    /// Returns `None` if a zero pivot is met.
+    pub(crate) fn solve(mut self, rhs: &mut Array2<f64>) -> Option<()> {
+        let n = self.n;
+        let cols = rhs.shape()[1];
+        let scale = self.data.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+
+        // Forward elimination
+        for k in 0..n {
+            let pivot = self.get(k, k);
+            if pivot.abs() <= f64::EPSILON * scale {
+                return None;
+            }
+
+            for i in k + 1..(k + self.lower + 1).min(n) {
+                let factor = self.get(i, k) / pivot;
+                if factor == 0.0 {
+                    continue;
+                }
+
+                for j in k..(k + self.upper + 1).min(n) {
+                    let value = self.get(i, j) - factor * self.get(k, j);
+                    self.set(i, j, value);
+                }
+                for c in 0..cols {
+                    rhs[[i, c]] -= factor * rhs[[k, c]];
+                }
+            }
+        }
+
+        // Back substitution
+        for k in (0..n).rev() {
+            for c in 0..cols {
+                let mut sum = rhs[[k, c]];
+                for j in k + 1..(k + self.upper + 1).min(n) {
+                    sum -= self.get(k, j) * rhs[[j, c]];
+                }
+                rhs[[k, c]] = sum / self.get(k, k);
+            }
+        }
+
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_tridiagonal_solve() {
+        // [2 1 0 0; 1 3 1 0; 0 1 4 1; 0 0 1 5] x = b with x = [1, -1, 2, 0.5]
+        let mut a = BandMatrix::zeros(4, 1, 1);
+        let diag = [2.0, 3.0, 4.0, 5.0];
+        for (i, &d) in diag.iter().enumerate() {
+            a.set(i, i, d);
+            if i + 1 < 4 {
+                a.set(i, i + 1, 1.0);
+                a.set(i + 1, i, 1.0);
+            }
+        }
+
+        let x = [1.0, -1.0, 2.0, 0.5];
+        let mut b = Array2::zeros((4, 2));
+        for i in 0..4 {
+            let row: f64 = (0..4).map(|j| a.get(i, j) * x[j]).sum();
+            b[[i, 0]] = row;
+            b[[i, 1]] = 2.0 * row;
+        }
+
+        a.solve(&mut b).unwrap();
+        for i in 0..4 {
+            assert_relative_eq!(b[[i, 0]], x[i], epsilon = 1e-14);
+            assert_relative_eq!(b[[i, 1]], 2.0 * x[i], epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_zero_pivot_is_reported() {
+        let mut a = BandMatrix::zeros(2, 1, 1);
+        a.set(0, 1, 1.0);
+        a.set(1, 0, 1.0);
+
+        assert!(a.solve(&mut Array2::ones((2, 1))).is_none());
+    }
+}
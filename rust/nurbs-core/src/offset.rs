@@ -0,0 +1,251 @@
+//! Offset surface approximation
+//!
+//! The offset O(u, v) = S(u, v) + d N(u, v) of a NURBS surface is not a
+//! NURBS surface in general. It is sampled exactly through the normals and
+//! fitted by a non-rational bicubic on the domain of S, refining the
+//! control grid until the fit is within tolerance.
+//!
+//! The offset folds over itself where d k >= 1 for a principal curvature
+//! k (signed with respect to N): there the normal lines cross within
+//! distance d and the exact offset has a cusp. Such samples are reported
+//! as hazards alongside the fit.
+
+use crate::derivatives::compute_curvature_info;
+use crate::error::NurbsError;
+use crate::fitting::{ApproximationOptions, SurfaceFit};
+use crate::refine::knot_multiplicity;
+use crate::surface::NURBSSurface;
+use crate::vector::{add, cross, norm, normalize, scale, sub};
+
+/// Largest control grid size per direction
+const MAX_CONTROL: usize = 32;
+
+/// Relative parameter step used to evaluate normals next to degenerate
+/// points such as the poles of a sphere
+const DEGENERATE_STEP: f64 = 1e-7;
+
+/// Sample where the offset distance reaches the radius of curvature
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetHazard {
+    pub u: f64,
+    pub v: f64,
+    /// Smallest radius of curvature on the side of the offset
+    pub radius: f64,
+}
+
+/// Approximated offset surface
+#[derive(Debug, Clone)]
+pub struct OffsetSurface {
+    pub surface: NURBSSurface,
+    /// Largest distance to the exact offset at the validation samples
+    pub max_error: f64,
+    /// Samples where the exact offset self-intersects locally
+    pub hazards: Vec<OffsetHazard>,
+}
+
+impl NURBSSurface {
+    /// Approximate the surface offset by `distance` along the unit normal
+    ///
+    /// The fit is a bicubic whose knots include those of `self` with the
+    /// same continuity. Its spans are halved until the error against the
+    /// exact offset, checked between the fitting samples, is at most
+    /// `tolerance`; if the control grid would exceed 32 points per
+    /// direction the best fit so far is returned with its `max_error`.
+    /// Near hazards the exact offset has cusps, so the tolerance may not be
+    /// reachable there.
+    pub fn offset(&self, distance: f64, tolerance: f64) -> Result<OffsetSurface, NurbsError> {
+        if !distance.is_finite() {
+            return Err(NurbsError::NonFinite { field: "distance" });
+        }
+        if !tolerance.is_finite() {
+            return Err(NurbsError::NonFinite { field: "tolerance" });
+        }
+        if tolerance <= 0.0 {
+            return Err(NurbsError::NonPositive { field: "tolerance", value: tolerance });
+        }
+
+        let mut best: Option<(SurfaceFit, Vec<[f64; 2]>)> = None;
+        let mut pieces = 1;
+        loop {
+            let knots_u = offset_knots(&self.knots_u, self.degree_u, pieces);
+            let knots_v = offset_knots(&self.knots_v, self.degree_v, pieces);
+            let (nu, nv) = (knots_u.len() - 4, knots_v.len() - 4);
+            if best.is_some() && nu.max(nv) > MAX_CONTROL {
+                break;
+            }
+
+            let samples_u = span_samples(&knots_u);
+            let samples_v = span_samples(&knots_v);
+            let mut points = Vec::with_capacity(samples_u.len() * samples_v.len());
+            let mut params = Vec::with_capacity(samples_u.len() * samples_v.len());
+            for &u in &samples_u {
+                for &v in &samples_v {
+                    points.push(self.offset_point(u, v, distance));
+                    params.push([u, v]);
+                }
+            }
+
+            let mut options = ApproximationOptions::new(3, 3, nu, nv);
+            options.knots = Some((knots_u, knots_v));
+            let mut fit = NURBSSurface::approximate(&points, &params, &options)?;
+
+            // Validate halfway between samples
+            let halfway = |samples: &[f64]| -> Vec<f64> {
+                samples.windows(2).map(|w| 0.5 * (w[0] + w[1])).chain(samples.iter().copied()).collect()
+            };
+            for &u in &halfway(&samples_u) {
+                for &v in &halfway(&samples_v) {
+                    let error = sub(&self.offset_point(u, v, distance), &fit.surface.evaluate(u, v));
+                    fit.max_error = fit.max_error.max(norm(&error));
+                }
+            }
+
+            let done = fit.max_error <= tolerance;
+            if best.as_ref().is_none_or(|(b, _)| fit.max_error < b.max_error) {
+                best = Some((fit, params));
+            }
+            if done {
+                break;
+            }
+            pieces *= 2;
+        }
+
+        let (fit, params) = best.expect("at least one fit is made");
+        Ok(OffsetSurface {
+            surface: fit.surface,
+            max_error: fit.max_error,
+            hazards: self.offset_hazards(distance, &params),
+        })
+    }
+
+    /// Exact offset point S + d N
+    fn offset_point(&self, u: f64, v: f64, distance: f64) -> [f64; 3] {
+        let (u, v) = self.regular_parameters(u, v);
+        let ders = self.derivatives(u, v, 1);
+        let normal = normalize(&cross(&ders[1][0], &ders[0][1])).unwrap_or([0.0; 3]);
+        add(&self.evaluate(u, v), &scale(&normal, distance))
+    }
+
+    /// Samples at which d k >= 1 for a principal curvature k
+    fn offset_hazards(&self, distance: f64, params: &[[f64; 2]]) -> Vec<OffsetHazard> {
+        params
+            .iter()
+            .filter_map(|&[u, v]| {
+                let (s, t) = self.regular_parameters(u, v);
+                let info = compute_curvature_info(self, s, t);
+
+                // k1 >= k2, so the binding curvature depends on the side
+                let k = if distance >= 0.0 { info.k1 } else { info.k2 };
+                (distance * k >= 1.0).then(|| OffsetHazard { u, v, radius: 1.0 / k.abs() })
+            })
+            .collect()
+    }
+
+    /// Parameters nudged towards the domain center where S_u x S_v vanishes
+    fn regular_parameters(&self, u: f64, v: f64) -> (f64, f64) {
+        let ders = self.derivatives(u, v, 1);
+        if normalize(&cross(&ders[1][0], &ders[0][1])).is_some() {
+            return (u, v);
+        }
+
+        let ([u0, u1], [v0, v1]) = self.domain();
+        let nudge = |t: f64, t0: f64, t1: f64| t + DEGENERATE_STEP * (t1 - t0) * (0.5 * (t0 + t1) - t).signum();
+        (nudge(u, u0, u1), nudge(v, v0, v1))
+    }
+}
+
+/// Cubic knots breaking at the distinct knots of a degree `degree` knot
+/// vector with the same continuity, each span split into `pieces`
+fn offset_knots(knots: &[f64], degree: usize, pieces: usize) -> Vec<f64> {
+    let mut breaks = knots[degree..knots.len() - degree].to_vec();
+    breaks.dedup();
+
+    let mut result = vec![breaks[0]; 4];
+    for (k, span) in breaks.windows(2).enumerate() {
+        let [a, b] = [span[0], span[1]];
+        result.extend((1..pieces).map(|i| a + (b - a) * i as f64 / pieces as f64));
+
+        // A knot of multiplicity m leaves C^(degree - m) continuity
+        let multiplicity = if k + 2 == breaks.len() {
+            4
+        } else {
+            (3 + knot_multiplicity(knots, b)).saturating_sub(degree).clamp(1, 3)
+        };
+        result.extend(std::iter::repeat_n(b, multiplicity));
+    }
+    result
+}
+
+/// Fitting samples: every distinct knot plus three more per span
+fn span_samples(knots: &[f64]) -> Vec<f64> {
+    let mut breaks = knots[3..knots.len() - 3].to_vec();
+    breaks.dedup();
+
+    let mut samples = vec![breaks[0]];
+    for span in breaks.windows(2) {
+        samples.extend((1..=4).map(|i| span[0] + (span[1] - span[0]) * i as f64 / 4.0));
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::distance;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_plane_offset_is_exact() {
+        let plane = NURBSSurface::plane([0.0; 3], [2.0, 0.0, 0.0], [0.0, 3.0, 0.0]);
+        let offset = plane.offset(0.75, 1e-9).unwrap();
+
+        assert!(offset.max_error < 1e-9);
+        assert!(offset.hazards.is_empty());
+        let p = offset.surface.evaluate(0.3, 0.6);
+        assert_relative_eq!(p[0], 0.6, epsilon = 1e-9);
+        assert_relative_eq!(p[1], 1.8, epsilon = 1e-9);
+        assert_relative_eq!(p[2], 0.75, epsilon = 1e-9);
+
+        assert_eq!(plane.offset(1.0, f64::NAN).unwrap_err(), NurbsError::NonFinite { field: "tolerance" });
+        assert_eq!(plane.offset(f64::INFINITY, 1e-3).unwrap_err(), NurbsError::NonFinite { field: "distance" });
+        assert_eq!(
+            plane.offset(1.0, 0.0).unwrap_err(),
+            NurbsError::NonPositive { field: "tolerance", value: 0.0 }
+        );
+    }
+
+    #[test]
+    fn test_sphere_offset_within_tolerance() {
+        let center = [0.5, 0.0, -1.0];
+        let sphere = NURBSSurface::sphere(center, 2.0);
+        let offset = sphere.offset(0.5, 1e-3).unwrap();
+
+        assert!(offset.max_error <= 1e-3, "max error {}", offset.max_error);
+        assert!(offset.hazards.is_empty());
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let p = offset.surface.evaluate(i as f64 / 10.0, j as f64 / 10.0);
+                assert_relative_eq!(distance(&p, &center), 2.5, epsilon = 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hazards_where_offset_exceeds_radius() {
+        // Inwards by less than the radius is safe, beyond it every sample folds
+        let cylinder = NURBSSurface::cylinder([0.0; 3], [0.0, 0.0, 1.0], 2.0, 3.0);
+        let inner = cylinder.offset(-1.5, 1e-3).unwrap();
+        assert!(inner.hazards.is_empty());
+        let p = inner.surface.evaluate(0.4, 0.5);
+        assert_relative_eq!(p[0].hypot(p[1]), 0.5, epsilon = 1e-3);
+
+        let folded = cylinder.offset(-2.5, 1e-3).unwrap();
+        assert!(!folded.hazards.is_empty());
+        for hazard in &folded.hazards {
+            assert_relative_eq!(hazard.radius, 2.0, epsilon = 1e-10);
+        }
+
+        // Outward offsets of convex shapes never fold
+        assert!(cylinder.offset(5.0, 1e-2).unwrap().hazards.is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+//! Exact primitive surfaces
+//!
+//! Quadrics and the torus are surfaces of revolution of lines and circular
+//! arcs, so they are represented exactly by rational quadratic arcs around
+//! the axis (NURBS Book, section 8.5). All primitives are defined on
+//! [0, 1] x [0, 1]; u runs around the axis counterclockwise and v along
+//! the profile, so that Su x Sv points outwards.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use ndarray::{Array1, Array2, Array3};
+
+use crate::construct::revolve;
+use crate::curve::NURBSCurve;
+use crate::error::NurbsError;
+use crate::surface::NURBSSurface;
+use crate::vector::{add, cross, normalize, perpendicular, scale};
+
+impl NURBSSurface {
+    /// Bilinear parallelogram spanned by `u_axis` and `v_axis` at `origin`
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_plane`.
+    pub fn plane(origin: [f64; 3], u_axis: [f64; 3], v_axis: [f64; 3]) -> NURBSSurface {
+        Self::try_plane(origin, u_axis, v_axis).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Bilinear parallelogram, failing if an axis is zero or the axes are
+    /// parallel
+    pub fn try_plane(origin: [f64; 3], u_axis: [f64; 3], v_axis: [f64; 3]) -> Result<NURBSSurface, NurbsError> {
+        let u_unit = unit_axis(&u_axis, "u axis")?;
+        let v_unit = unit_axis(&v_axis, "v axis")?;
+        if normalize(&cross(&u_unit, &v_unit)).is_none() {
+            return Err(NurbsError::ParallelVectors { first: "u axis", second: "v axis" });
+        }
+
+        let mut control_points = Array3::zeros((2, 2, 3));
+        for i in 0..2 {
+            for j in 0..2 {
+                let corner = add(&origin, &add(&scale(&u_axis, i as f64), &scale(&v_axis, j as f64)));
+                for d in 0..3 {
+                    control_points[[i, j, d]] = corner[d];
+                }
+            }
+        }
+
+        NURBSSurface::try_new(
+            1,
+            1,
+            control_points,
+            Array2::ones((2, 2)),
+            vec![0.0, 0.0, 1.0, 1.0],
+            vec![0.0, 0.0, 1.0, 1.0],
+        )
+    }
+
+    /// Circular cylinder standing on `base_center` along `axis`
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_cylinder`.
+    pub fn cylinder(base_center: [f64; 3], axis: [f64; 3], radius: f64, height: f64) -> NURBSSurface {
+        Self::try_cylinder(base_center, axis, radius, height).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Circular cylinder, failing if `axis` is zero or `radius` or `height`
+    /// is not positive
+    pub fn try_cylinder(
+        base_center: [f64; 3],
+        axis: [f64; 3],
+        radius: f64,
+        height: f64,
+    ) -> Result<NURBSSurface, NurbsError> {
+        if radius.is_nan() || radius <= 0.0 {
+            return Err(NurbsError::InvalidRadius { field: "radius", value: radius });
+        }
+        Self::try_cone(base_center, axis, radius, radius, height)
+    }
+
+    /// Truncated cone from `base_radius` at `base_center` to `top_radius`
+    /// at `height` along `axis`
+    ///
+    /// A zero radius collapses that end to an apex.
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_cone`.
+    pub fn cone(
+        base_center: [f64; 3],
+        axis: [f64; 3],
+        base_radius: f64,
+        top_radius: f64,
+        height: f64,
+    ) -> NURBSSurface {
+        Self::try_cone(base_center, axis, base_radius, top_radius, height).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Truncated cone, failing if `axis` is zero, a radius is negative,
+    /// both radii are zero or `height` is not positive
+    pub fn try_cone(
+        base_center: [f64; 3],
+        axis: [f64; 3],
+        base_radius: f64,
+        top_radius: f64,
+        height: f64,
+    ) -> Result<NURBSSurface, NurbsError> {
+        for (field, value) in [("base radius", base_radius), ("top radius", top_radius)] {
+            if value.is_nan() || value < 0.0 {
+                return Err(NurbsError::InvalidRadius { field, value });
+            }
+        }
+        if base_radius + top_radius == 0.0 {
+            return Err(NurbsError::InvalidRadius { field: "sum of base and top radius", value: 0.0 });
+        }
+        if !height.is_finite() {
+            return Err(NurbsError::NonFinite { field: "height" });
+        }
+        if height <= 0.0 {
+            return Err(NurbsError::NonPositive { field: "height", value: height });
+        }
+        let axis = unit_axis(&axis, "axis")?;
+        let x = perpendicular(&axis);
+
+        let bottom = add(&base_center, &scale(&x, base_radius));
+        let top = add(&add(&base_center, &scale(&axis, height)), &scale(&x, top_radius));
+        let profile = NURBSCurve::try_new(
+            1,
+            Array2::from_shape_vec((2, 3), [bottom, top].concat()).unwrap(),
+            Array1::ones(2),
+            vec![0.0, 0.0, 1.0, 1.0],
+        )?;
+
+        revolve(&profile, base_center, axis, TAU)
+    }
+
+    /// Sphere of `radius` about `center` with poles on the z axis
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_sphere`.
+    pub fn sphere(center: [f64; 3], radius: f64) -> NURBSSurface {
+        Self::try_sphere(center, radius).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Sphere, failing if `radius` is not positive
+    pub fn try_sphere(center: [f64; 3], radius: f64) -> Result<NURBSSurface, NurbsError> {
+        if radius.is_nan() || radius <= 0.0 {
+            return Err(NurbsError::InvalidRadius { field: "radius", value: radius });
+        }
+
+        // Meridian from the south to the north pole
+        let axis = [0.0, 0.0, 1.0];
+        let meridian = NURBSCurve::circular_arc(center, [1.0, 0.0, 0.0], axis, radius, -FRAC_PI_2, FRAC_PI_2);
+
+        revolve(&meridian, center, axis, TAU)
+    }
+
+    /// Torus about `axis` through `center`, with tube radius
+    /// `minor_radius` around a circle of `major_radius`
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_torus`.
+    pub fn torus(center: [f64; 3], axis: [f64; 3], major_radius: f64, minor_radius: f64) -> NURBSSurface {
+        Self::try_torus(center, axis, major_radius, minor_radius).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Torus, failing if `axis` is zero or a radius is not positive
+    pub fn try_torus(
+        center: [f64; 3],
+        axis: [f64; 3],
+        major_radius: f64,
+        minor_radius: f64,
+    ) -> Result<NURBSSurface, NurbsError> {
+        for (field, value) in [("major radius", major_radius), ("minor radius", minor_radius)] {
+            if value.is_nan() || value <= 0.0 {
+                return Err(NurbsError::InvalidRadius { field, value });
+            }
+        }
+        let axis = unit_axis(&axis, "axis")?;
+        let x = perpendicular(&axis);
+
+        // Tube cross-section, starting on the outer equator
+        let tube_center = add(&center, &scale(&x, major_radius));
+        let section = NURBSCurve::circular_arc(tube_center, x, axis, minor_radius, 0.0, TAU);
+
+        revolve(&section, center, axis, TAU)
+    }
+}
+
+/// Unit vector along the named axis, failing if it is zero or not finite
+fn unit_axis(axis: &[f64; 3], field: &'static str) -> Result<[f64; 3], NurbsError> {
+    if axis.iter().any(|c| !c.is_finite()) {
+        return Err(NurbsError::NonFinite { field });
+    }
+    normalize(axis).ok_or(NurbsError::ZeroVector { field })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derivatives::{compute_curvature_info, compute_normal};
+    use crate::vector::{distance, dot, norm, sub};
+    use approx::assert_relative_eq;
+
+    fn point(v: &[f64]) -> [f64; 3] {
+        [v[0], v[1], v[2]]
+    }
+
+    fn samples() -> impl Iterator<Item = (f64, f64)> {
+        (0..=8).flat_map(|i| (0..=8).map(move |j| (i as f64 / 8.0, j as f64 / 8.0)))
+    }
+
+    #[test]
+    fn test_sphere_is_exact() {
+        let center = [1.0, -2.0, 0.5];
+        let sphere = NURBSSurface::sphere(center, 3.0);
+
+        for (u, v) in samples() {
+            let p = point(&sphere.evaluate(u, v));
+            assert_relative_eq!(distance(&p, &center), 3.0, epsilon = 1e-12);
+        }
+
+        // Outward normals and umbilic curvature away from the poles
+        for &(u, v) in &[(0.1, 0.3), (0.5, 0.5), (0.8, 0.7)] {
+            let p = point(&sphere.evaluate(u, v));
+            let n = compute_normal(&sphere, u, v);
+            assert_relative_eq!(dot(&n, &sub(&p, &center)), 3.0, epsilon = 1e-10);
+
+            let info = compute_curvature_info(&sphere, u, v);
+            assert_relative_eq!(info.gaussian, 1.0 / 9.0, epsilon = 1e-10);
+            assert_relative_eq!(info.mean.abs(), 1.0 / 3.0, epsilon = 1e-10);
+            assert!(info.umbilic);
+        }
+    }
+
+    #[test]
+    fn test_cylinder_and_cone() {
+        let axis = [1.0, 1.0, 0.0];
+        let unit = normalize(&axis).unwrap();
+        let cylinder = NURBSSurface::cylinder([0.0; 3], axis, 2.0, 5.0);
+        let cone = NURBSSurface::cone([0.0; 3], axis, 2.0, 0.0, 4.0);
+
+        for (u, v) in samples() {
+            let p = point(&cylinder.evaluate(u, v));
+            let along = dot(&p, &unit);
+            assert_relative_eq!(along, 5.0 * v, epsilon = 1e-12);
+            assert_relative_eq!(norm(&sub(&p, &scale(&unit, along))), 2.0, epsilon = 1e-12);
+
+            let q = point(&cone.evaluate(u, v));
+            let along = dot(&q, &unit);
+            assert_relative_eq!(norm(&sub(&q, &scale(&unit, along))), 2.0 * (1.0 - along / 4.0), epsilon = 1e-12);
+        }
+
+        let info = compute_curvature_info(&cylinder, 0.3, 0.4);
+        assert_relative_eq!(info.gaussian, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(info.mean.abs(), 0.25, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_torus_and_plane() {
+        let (major, minor) = (3.0, 1.0);
+        let torus = NURBSSurface::torus([0.0; 3], [0.0, 0.0, 1.0], major, minor);
+
+        for (u, v) in samples() {
+            let p = torus.evaluate(u, v);
+            let ring = p[0].hypot(p[1]) - major;
+            assert_relative_eq!(ring.hypot(p[2]), minor, epsilon = 1e-12);
+        }
+
+        // v = 0 is the outer equator, where K = cos(0) / (r (R + r cos(0)))
+        let info = compute_curvature_info(&torus, 0.35, 0.0);
+        assert_relative_eq!(info.gaussian, 1.0 / (minor * (major + minor)), epsilon = 1e-10);
+
+        let plane = NURBSSurface::plane([1.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 0.0, 3.0]);
+        let p = plane.evaluate(0.5, 0.25);
+        assert_relative_eq!(p[0], 2.0, epsilon = 1e-14);
+        assert_relative_eq!(p[2], 0.75, epsilon = 1e-14);
+        assert_relative_eq!(compute_normal(&plane, 0.5, 0.5)[1], -1.0, epsilon = 1e-14);
+    }
+
+    #[test]
+    fn test_invalid_primitive_input() {
+        assert_eq!(
+            NURBSSurface::try_sphere([0.0; 3], -1.0).unwrap_err(),
+            NurbsError::InvalidRadius { field: "radius", value: -1.0 }
+        );
+        assert_eq!(
+            NURBSSurface::try_cylinder([0.0; 3], [0.0; 3], 1.0, 2.0).unwrap_err(),
+            NurbsError::ZeroVector { field: "axis" }
+        );
+        assert!(NURBSSurface::try_torus([0.0; 3], [0.0, 0.0, 1.0], 2.0, f64::NAN).is_err());
+        assert!(NURBSSurface::try_cone([0.0; 3], [0.0, 0.0, 1.0], 0.0, 1.0, 1.0).is_ok());
+
+        let z = [0.0, 0.0, 1.0];
+        assert_eq!(
+            NURBSSurface::try_cone([0.0; 3], z, 0.0, 0.0, 1.0).unwrap_err(),
+            NurbsError::InvalidRadius { field: "sum of base and top radius", value: 0.0 }
+        );
+        assert_eq!(
+            NURBSSurface::try_cylinder([0.0; 3], z, 1.0, 0.0).unwrap_err(),
+            NurbsError::NonPositive { field: "height", value: 0.0 }
+        );
+        for height in [f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                NURBSSurface::try_cylinder([0.0; 3], z, 1.0, height).unwrap_err(),
+                NurbsError::NonFinite { field: "height" }
+            );
+        }
+        assert_eq!(
+            NURBSSurface::try_cylinder([0.0; 3], [f64::INFINITY, 0.0, 0.0], 1.0, 1.0).unwrap_err(),
+            NurbsError::NonFinite { field: "axis" }
+        );
+        assert_eq!(
+            NURBSSurface::try_torus([0.0; 3], [0.0, f64::NEG_INFINITY, 1.0], 2.0, 1.0).unwrap_err(),
+            NurbsError::NonFinite { field: "axis" }
+        );
+
+        // Planes need two non-zero, non-parallel axes
+        let x = [1.0, 0.0, 0.0];
+        assert!(NURBSSurface::try_plane([0.0; 3], x, z).is_ok());
+        assert_eq!(
+            NURBSSurface::try_plane([0.0; 3], [0.0; 3], z).unwrap_err(),
+            NurbsError::ZeroVector { field: "u axis" }
+        );
+        assert_eq!(
+            NURBSSurface::try_plane([0.0; 3], x, [-2.0, 0.0, 0.0]).unwrap_err(),
+            NurbsError::ParallelVectors { first: "u axis", second: "v axis" }
+        );
+    }
+}
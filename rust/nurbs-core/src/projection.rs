@@ -0,0 +1,299 @@
+//! Point inversion and closest-point projection
+//!
+//! Seeds come from a coarse `evaluate_grid` sample; each seed is refined by
+//! Newton iteration on the squared distance using analytic derivatives
+//! (NURBS Book, section 6.1). Parameters are clamped to the knot domain, and
+//! on a boundary the iteration continues along the free direction only.
+
+use crate::surface::NURBSSurface;
+use crate::vector::{add, dot, norm, scale, sub};
+
+/// Convergence tolerance on point coincidence and parameter steps
+const POINT_TOLERANCE: f64 = 1e-12;
+
+/// Convergence tolerance on the cosine between residual and tangents
+const COSINE_TOLERANCE: f64 = 1e-12;
+
+const MAX_ITERATIONS: usize = 50;
+
+/// Number of grid seeds refined by Newton iteration
+const SEED_COUNT: usize = 4;
+
+/// Result of projecting a point onto a surface
+#[derive(Debug, Clone, Copy)]
+pub struct ClosestPoint {
+    pub u: f64,
+    pub v: f64,
+    pub point: [f64; 3],
+    pub distance: f64,
+    /// Whether Newton iteration met its tolerance before the iteration cap
+    pub converged: bool,
+}
+
+impl NURBSSurface {
+    /// Closest point on the surface to `p`
+    pub fn closest_point(&self, p: [f64; 3]) -> ClosestPoint {
+        let (u_res, v_res) = self.dimensions();
+        let u_samples = (3 * u_res).clamp(10, 100);
+        let v_samples = (3 * v_res).clamp(10, 100);
+
+        let grid = self.evaluate_grid(u_samples, v_samples);
+        let ([u0, u1], [v0, v1]) = self.domain();
+
+        // Rank grid samples by distance to p
+        let mut seeds: Vec<(f64, usize, usize)> = Vec::with_capacity(u_samples * v_samples);
+        for i in 0..u_samples {
+            for j in 0..v_samples {
+                let d = (0..3).map(|k| (grid[[i, j, k]] - p[k]).powi(2)).sum::<f64>();
+                seeds.push((d, i, j));
+            }
+        }
+        seeds.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        seeds
+            .iter()
+            .take(SEED_COUNT)
+            .map(|&(_, i, j)| {
+                let u = u0 + (u1 - u0) * i as f64 / (u_samples - 1) as f64;
+                let v = v0 + (v1 - v0) * j as f64 / (v_samples - 1) as f64;
+                self.closest_point_from(p, u, v)
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .unwrap()
+    }
+
+    /// Closest point to `p` by Newton iteration from the seed (u, v)
+    ///
+    /// Useful when a good starting parameter is already known, e.g. when
+    /// projecting a sequence of nearby points.
+    pub fn closest_point_from(&self, p: [f64; 3], u: f64, v: f64) -> ClosestPoint {
+        let ([u_min, u_max], [v_min, v_max]) = self.domain();
+
+        let mut u = u.clamp(u_min, u_max);
+        let mut v = v.clamp(v_min, v_max);
+        let mut converged = false;
+
+        for _ in 0..MAX_ITERATIONS {
+            let ders = self.derivatives(u, v, 2);
+            let s = ders[0][0];
+            let su = ders[1][0];
+            let sv = ders[0][1];
+            let suu = ders[2][0];
+            let suv = ders[1][1];
+            let svv = ders[0][2];
+
+            let r = sub(&s, &p);
+            let r_len = norm(&r);
+            if !r_len.is_finite() {
+                break;
+            }
+
+            // Point coincidence
+            if r_len <= POINT_TOLERANCE {
+                converged = true;
+                break;
+            }
+
+            // Zero cosine: residual is orthogonal to both tangents
+            let f = dot(&r, &su);
+            let g = dot(&r, &sv);
+            let cos_u = f.abs() / (norm(&su) * r_len).max(f64::MIN_POSITIVE);
+            let cos_v = g.abs() / (norm(&sv) * r_len).max(f64::MIN_POSITIVE);
+
+            let free_u = !(u <= u_min && f > 0.0 || u >= u_max && f < 0.0);
+            let free_v = !(v <= v_min && g > 0.0 || v >= v_max && g < 0.0);
+
+            if (cos_u <= COSINE_TOLERANCE || !free_u) && (cos_v <= COSINE_TOLERANCE || !free_v) {
+                converged = true;
+                break;
+            }
+
+            // Newton step on the gradient of |S - P|^2 / 2
+            let j11 = dot(&su, &su) + dot(&r, &suu);
+            let j12 = dot(&su, &sv) + dot(&r, &suv);
+            let j22 = dot(&sv, &sv) + dot(&r, &svv);
+
+            // Gradient steps are used where the Hessian is not positive
+            let step_u = if j11 > 0.0 { -f / j11 } else { -f / dot(&su, &su).max(f64::MIN_POSITIVE) };
+            let step_v = if j22 > 0.0 { -g / j22 } else { -g / dot(&sv, &sv).max(f64::MIN_POSITIVE) };
+
+            let (mut du, mut dv) = match (free_u, free_v) {
+                (true, true) => {
+                    let det = j11 * j22 - j12 * j12;
+                    if j11 > 0.0 && det > f64::EPSILON * j11 * j22 {
+                        ((-f * j22 + g * j12) / det, (-g * j11 + f * j12) / det)
+                    } else {
+                        (step_u, step_v)
+                    }
+                }
+                (true, false) => (step_u, 0.0),
+                (false, true) => (0.0, step_v),
+                (false, false) => (0.0, 0.0),
+            };
+
+            // Damp the step until the distance does not increase
+            let mut accepted = false;
+            for _ in 0..10 {
+                let nu = (u + du).clamp(u_min, u_max);
+                let nv = (v + dv).clamp(v_min, v_max);
+                let q = self.evaluate(nu, nv);
+
+                if norm(&sub(&q, &p)) <= r_len * (1.0 + 1e-12) {
+                    du = nu - u;
+                    dv = nv - v;
+                    accepted = true;
+                    break;
+                }
+
+                du *= 0.5;
+                dv *= 0.5;
+            }
+
+            // Stalled: no step along the derivatives gets closer
+            if !accepted {
+                break;
+            }
+
+            u += du;
+            v += dv;
+
+            // Parameters no longer move the point significantly
+            let step = add(&scale(&su, du), &scale(&sv, dv));
+            if norm(&step) <= POINT_TOLERANCE {
+                converged = true;
+                break;
+            }
+        }
+
+        let point = self.evaluate(u, v);
+        ClosestPoint {
+            u,
+            v,
+            point,
+            distance: norm(&sub(&point, &p)),
+            converged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derivatives::compute_normal;
+    use approx::assert_relative_eq;
+    use ndarray::{Array2, Array3};
+
+    fn create_dome() -> NURBSSurface {
+        let u_res = 5;
+        let v_res = 5;
+
+        let mut control_points = Array3::zeros((u_res, v_res, 3));
+        let mut weights = Array2::ones((u_res, v_res));
+        for i in 0..u_res {
+            for j in 0..v_res {
+                let x = i as f64 / 4.0 - 0.5;
+                let y = j as f64 / 4.0 - 0.5;
+                control_points[[i, j, 0]] = x;
+                control_points[[i, j, 1]] = y;
+                control_points[[i, j, 2]] = 0.5 - x * x - y * y;
+                weights[[i, j]] = 1.0 + 0.5 * ((i + j) % 2) as f64;
+            }
+        }
+
+        let knots = vec![0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0];
+        NURBSSurface::new(3, 3, control_points, weights, knots.clone(), knots)
+    }
+
+    #[test]
+    fn test_point_inversion() {
+        let surface = create_dome();
+
+        for &(u, v) in &[(0.2, 0.7), (0.5, 0.5), (0.9, 0.1), (0.0, 0.3)] {
+            let p = surface.evaluate(u, v);
+            let result = surface.closest_point(p);
+
+            assert!(result.converged);
+            assert_relative_eq!(result.u, u, epsilon = 1e-8);
+            assert_relative_eq!(result.v, v, epsilon = 1e-8);
+            assert!(result.distance < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_projection_along_normal() {
+        let surface = create_dome();
+        let (u, v) = (0.35, 0.6);
+
+        let s = surface.evaluate(u, v);
+        let n = compute_normal(&surface, u, v);
+        let offset = 0.05;
+        let p = [s[0] + offset * n[0], s[1] + offset * n[1], s[2] + offset * n[2]];
+
+        let result = surface.closest_point(p);
+
+        assert!(result.converged);
+        assert_relative_eq!(result.u, u, epsilon = 1e-7);
+        assert_relative_eq!(result.v, v, epsilon = 1e-7);
+        assert_relative_eq!(result.distance, offset, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_projection_clamps_to_boundary() {
+        // Bilinear unit square in the xy plane
+        let mut control_points = Array3::zeros((2, 2, 3));
+        for i in 0..2 {
+            for j in 0..2 {
+                control_points[[i, j, 0]] = i as f64;
+                control_points[[i, j, 1]] = j as f64;
+            }
+        }
+        let knots = vec![0.0, 0.0, 1.0, 1.0];
+        let square = NURBSSurface::new(1, 1, control_points, Array2::ones((2, 2)), knots.clone(), knots);
+
+        // Beyond the u = 1 edge: the closest point lies on that edge
+        let result = square.closest_point([1.5, 0.25, 0.3]);
+        assert!(result.converged);
+        assert_relative_eq!(result.u, 1.0, epsilon = 1e-12);
+        assert_relative_eq!(result.v, 0.25, epsilon = 1e-10);
+        assert_relative_eq!(result.distance, (0.25f64 + 0.09).sqrt(), epsilon = 1e-10);
+
+        // Beyond a corner
+        let corner = square.closest_point([-1.0, -2.0, 0.0]);
+        assert!(corner.converged);
+        assert_eq!((corner.u, corner.v), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_projection_of_nan_point() {
+        let result = create_dome().closest_point([f64::NAN, 0.0, 0.0]);
+        assert!(!result.converged);
+        assert!(result.distance.is_nan());
+    }
+
+    #[test]
+    fn test_stalled_projection_is_not_converged() {
+        // V-shaped fold along u = 0.5; at the crease the one-sided derivative
+        // does not vanish, yet every step towards either side moves away
+        let mut control_points = Array3::zeros((3, 2, 3));
+        for i in 0..3 {
+            for j in 0..2 {
+                control_points[[i, j, 0]] = i as f64 - 1.0;
+                control_points[[i, j, 1]] = j as f64;
+                control_points[[i, j, 2]] = (i as f64 - 1.0).abs();
+            }
+        }
+        let fold = NURBSSurface::new(
+            1,
+            1,
+            control_points,
+            Array2::ones((3, 2)),
+            vec![0.0, 0.0, 0.5, 1.0, 1.0],
+            vec![0.0, 0.0, 1.0, 1.0],
+        );
+
+        let result = fold.closest_point_from([0.0, 0.5, -1.0], 0.5, 0.5);
+        assert!(!result.converged);
+        assert_eq!((result.u, result.v), (0.5, 0.5));
+        assert_relative_eq!(result.distance, 1.0, epsilon = 1e-12);
+    }
+}
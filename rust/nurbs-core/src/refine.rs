@@ -0,0 +1,395 @@
+//! Knot insertion and knot refinement
+//!
+//! The kernels operate on one row of homogeneous control points, so rational
+//! surfaces are refined exactly by applying them to every row in the
+//! insertion direction.
+
+use ndarray::{s, Array2};
+
+use crate::basis::CoxDeBoor;
+use crate::curve::{is_clamped, NURBSCurve};
+use crate::surface::{Direction, NURBSSurface};
+
+impl NURBSSurface {
+    /// Insert knot `t` into the u knot vector `times` times (Boehm's algorithm)
+    ///
+    /// The resulting multiplicity is capped at the degree, so inserting an
+    /// existing end knot of a clamped surface is a no-op.
+    pub fn insert_knot_u(&self, t: f64, times: usize) -> NURBSSurface {
+        self.map_rows(Direction::U, |knots, p, row| {
+            let (k, r) = insert_knot(knots, p, row, t, times);
+            (k, p, r)
+        })
+    }
+
+    /// Insert knot `t` into the v knot vector `times` times (Boehm's algorithm)
+    pub fn insert_knot_v(&self, t: f64, times: usize) -> NURBSSurface {
+        self.map_rows(Direction::V, |knots, q, row| {
+            let (k, r) = insert_knot(knots, q, row, t, times);
+            (k, q, r)
+        })
+    }
+
+    /// Insert all knots of `new_knots` into the u knot vector at once
+    ///
+    /// Values outside the open parameter domain are ignored.
+    pub fn refine_knots_u(&self, new_knots: &[f64]) -> NURBSSurface {
+        self.map_rows(Direction::U, |knots, p, row| {
+            let (k, r) = refine_knots(knots, p, row, new_knots);
+            (k, p, r)
+        })
+    }
+
+    /// Insert all knots of `new_knots` into the v knot vector at once
+    ///
+    /// Values outside the open parameter domain are ignored.
+    pub fn refine_knots_v(&self, new_knots: &[f64]) -> NURBSSurface {
+        self.map_rows(Direction::V, |knots, q, row| {
+            let (k, r) = refine_knots(knots, q, row, new_knots);
+            (k, q, r)
+        })
+    }
+
+    /// Equivalent surface with clamped knot vectors in both directions
+    ///
+    /// The shape over the parameter domain is unchanged; control points
+    /// that only influence the surface outside the domain are dropped.
+    pub fn clamped(&self) -> NURBSSurface {
+        let surface = if is_clamped(&self.knots_u, self.degree_u) {
+            self.clone()
+        } else {
+            self.map_rows(Direction::U, |knots, p, row| {
+                let (k, r) = clamp_knots(knots, p, row);
+                (k, p, r)
+            })
+        };
+
+        if is_clamped(&surface.knots_v, surface.degree_v) {
+            surface
+        } else {
+            surface.map_rows(Direction::V, |knots, q, row| {
+                let (k, r) = clamp_knots(knots, q, row);
+                (k, q, r)
+            })
+        }
+    }
+}
+
+impl NURBSCurve {
+    /// Equivalent curve with a clamped knot vector
+    pub fn clamped(&self) -> NURBSCurve {
+        if self.is_clamped() {
+            return self.clone();
+        }
+
+        let (knots, pw) = clamp_knots(&self.knots, self.degree, &self.homogeneous());
+        NURBSCurve::from_homogeneous(self.degree, &pw, knots)
+    }
+}
+
+/// Number of times `t` appears in the knot vector
+pub(crate) fn knot_multiplicity(knots: &[f64], t: f64) -> usize {
+    knots.iter().filter(|&&k| k == t).count()
+}
+
+/// Insert `t` up to `times` times into a curve with homogeneous points `pw`
+/// (NURBS Book, A5.1)
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn insert_knot(
+    knots: &[f64],
+    degree: usize,
+    pw: &Array2<f64>,
+    t: f64,
+    times: usize,
+) -> (Vec<f64>, Array2<f64>) {
+    let p = degree;
+    let n = pw.shape()[0];
+    let dim = pw.shape()[1];
+    let s = knot_multiplicity(knots, t);
+    let r = times.min(p.saturating_sub(s));
+
+    if r == 0 || t < knots[p] || t > knots[n] {
+        return (knots.to_vec(), pw.clone());
+    }
+
+    // Span with knots[k] <= t < knots[k + 1]; unlike find_span this does not
+    // fold the domain end into the last span of an unclamped knot vector
+    let k = knots.iter().rposition(|&u| u <= t).unwrap();
+
+    // New knot vector
+    let mut new_knots = Vec::with_capacity(knots.len() + r);
+    new_knots.extend_from_slice(&knots[..=k]);
+    new_knots.extend(std::iter::repeat_n(t, r));
+    new_knots.extend_from_slice(&knots[k + 1..]);
+
+    // Unaffected control points
+    let mut qw = Array2::zeros((n + r, dim));
+    for i in 0..=k - p {
+        qw.row_mut(i).assign(&pw.row(i));
+    }
+    for i in k - s..n {
+        qw.row_mut(i + r).assign(&pw.row(i));
+    }
+
+    let mut rw: Vec<Vec<f64>> = (0..=p - s).map(|i| pw.row(k - p + i).to_vec()).collect();
+
+    let mut l = k - p;
+    for j in 1..=r {
+        l = k - p + j;
+        for i in 0..=p - j - s {
+            let alpha = (t - knots[l + i]) / (knots[i + k + 1] - knots[l + i]);
+            for d in 0..dim {
+                rw[i][d] = alpha * rw[i + 1][d] + (1.0 - alpha) * rw[i][d];
+            }
+        }
+
+        for d in 0..dim {
+            qw[[l, d]] = rw[0][d];
+            qw[[k + r - j - s, d]] = rw[p - j - s][d];
+        }
+    }
+
+    for i in l + 1..k - s {
+        for d in 0..dim {
+            qw[[i, d]] = rw[i - l][d];
+        }
+    }
+
+    (new_knots, qw)
+}
+
+/// Clamp both ends of a curve with homogeneous points `pw`
+///
+/// The domain ends are inserted up to multiplicity p, after which the
+/// control points outside the domain no longer contribute and are dropped.
+/// The end is handled as the start of the reversed curve.
+pub(crate) fn clamp_knots(knots: &[f64], degree: usize, pw: &Array2<f64>) -> (Vec<f64>, Array2<f64>) {
+    let reverse = |knots: &[f64], pw: &Array2<f64>| -> (Vec<f64>, Array2<f64>) {
+        let reversed: Vec<f64> = knots.iter().rev().map(|k| -k).collect();
+        (reversed, pw.slice(s![..;-1, ..]).to_owned())
+    };
+
+    let (knots, pw) = clamp_start(knots, degree, pw);
+    let (knots, pw) = reverse(&knots, &pw);
+    let (knots, pw) = clamp_start(&knots, degree, &pw);
+    reverse(&knots, &pw)
+}
+
+/// Clamp the start of a knot vector at knots[p]
+fn clamp_start(knots: &[f64], degree: usize, pw: &Array2<f64>) -> (Vec<f64>, Array2<f64>) {
+    let p = degree;
+    let a = knots[p];
+    let (knots, pw) = insert_knot(knots, p, pw, a, p);
+
+    // With a of multiplicity at least p, the basis functions before `first`
+    // vanish on the domain
+    let start = knots.iter().position(|&k| k == a).unwrap_or(0);
+    let multiplicity = knot_multiplicity(&knots, a);
+    let first = (start + multiplicity).saturating_sub(p + 1);
+
+    let mut clamped = vec![a; p + 1];
+    clamped.extend_from_slice(&knots[start + multiplicity..]);
+    let pw = pw.slice(s![first.., ..]).to_owned();
+    (clamped, pw)
+}
+
+/// Insert a sorted set of knots into a curve with homogeneous points `pw`
+/// (NURBS Book, A5.4)
+pub(crate) fn refine_knots(
+    knots: &[f64],
+    degree: usize,
+    pw: &Array2<f64>,
+    new_knots: &[f64],
+) -> (Vec<f64>, Array2<f64>) {
+    let p = degree;
+    let n = pw.shape()[0] - 1;
+    let m = n + p + 1;
+    let dim = pw.shape()[1];
+
+    let mut x: Vec<f64> = new_knots
+        .iter()
+        .copied()
+        .filter(|&t| t > knots[p] && t < knots[n + 1])
+        .collect();
+    x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    if x.is_empty() {
+        return (knots.to_vec(), pw.clone());
+    }
+
+    let r = x.len() - 1;
+    let a = CoxDeBoor::find_span(x[0], p, knots);
+    let b = CoxDeBoor::find_span(x[r], p, knots) + 1;
+
+    let mut ubar = vec![0.0; m + r + 2];
+    let mut qw = Array2::zeros((n + r + 2, dim));
+
+    for j in 0..=a - p {
+        qw.row_mut(j).assign(&pw.row(j));
+    }
+    for j in b - 1..=n {
+        qw.row_mut(j + r + 1).assign(&pw.row(j));
+    }
+    ubar[..=a].copy_from_slice(&knots[..=a]);
+    for j in b + p..=m {
+        ubar[j + r + 1] = knots[j];
+    }
+
+    let mut i = b + p - 1;
+    let mut k = b + p + r;
+
+    for j in (0..=r).rev() {
+        while x[j] <= knots[i] && i > a {
+            let src = pw.row(i - p - 1).to_owned();
+            qw.row_mut(k - p - 1).assign(&src);
+            ubar[k] = knots[i];
+            k -= 1;
+            i -= 1;
+        }
+
+        let src = qw.row(k - p).to_owned();
+        qw.row_mut(k - p - 1).assign(&src);
+
+        for l in 1..=p {
+            let ind = k - p + l;
+            let mut alpha = ubar[k + l] - x[j];
+
+            if alpha.abs() == 0.0 {
+                let src = qw.row(ind).to_owned();
+                qw.row_mut(ind - 1).assign(&src);
+            } else {
+                alpha /= ubar[k + l] - knots[i + l - p];
+                for d in 0..dim {
+                    qw[[ind - 1, d]] = alpha * qw[[ind - 1, d]] + (1.0 - alpha) * qw[[ind, d]];
+                }
+            }
+        }
+
+        ubar[k] = x[j];
+        k -= 1;
+    }
+
+    (ubar, qw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::rational_patch;
+    use approx::assert_relative_eq;
+
+    fn assert_same_shape(a: &NURBSSurface, b: &NURBSSurface) {
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let (u, v) = (i as f64 / 10.0, j as f64 / 10.0);
+                let pa = a.evaluate(u, v);
+                let pb = b.evaluate(u, v);
+                for k in 0..3 {
+                    assert_relative_eq!(pa[k], pb[k], epsilon = 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_knot_preserves_shape() {
+        let surface = rational_patch();
+
+        let refined = surface.insert_knot_u(0.7, 2).insert_knot_v(0.5, 1);
+
+        assert_eq!(refined.dimensions(), (7, 5));
+        assert_eq!(knot_multiplicity(&refined.knots_u, 0.7), 2);
+        assert_eq!(knot_multiplicity(&refined.knots_v, 0.5), 2);
+        assert_same_shape(&surface, &refined);
+    }
+
+    #[test]
+    fn test_insert_knot_caps_multiplicity() {
+        let surface = rational_patch();
+
+        let refined = surface.insert_knot_u(0.4, 10);
+        assert_eq!(knot_multiplicity(&refined.knots_u, 0.4), 3);
+
+        let unchanged = surface.insert_knot_u(1.0, 1);
+        assert_eq!(unchanged.knots_u, surface.knots_u);
+    }
+
+    #[test]
+    fn test_refine_knots_preserves_shape() {
+        let surface = rational_patch();
+
+        let refined = surface
+            .refine_knots_u(&[0.1, 0.4, 0.4, 0.75, 0.9])
+            .refine_knots_v(&[0.25, 0.5, 0.8]);
+
+        assert_eq!(refined.dimensions(), (10, 7));
+        assert_eq!(
+            refined.knots_u,
+            vec![0.0, 0.0, 0.0, 0.0, 0.1, 0.4, 0.4, 0.4, 0.75, 0.9, 1.0, 1.0, 1.0, 1.0]
+        );
+        assert_same_shape(&surface, &refined);
+
+        // Bulk refinement agrees with repeated single insertion
+        let single = surface.insert_knot_v(0.25, 1).insert_knot_v(0.5, 1).insert_knot_v(0.8, 1);
+        let bulk = surface.refine_knots_v(&[0.25, 0.5, 0.8]);
+        assert_eq!(single.knots_v, bulk.knots_v);
+        for (a, b) in single.control_points.iter().zip(bulk.control_points.iter()) {
+            assert_relative_eq!(a, b, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_clamped_preserves_shape() {
+        // Uniform knots in u, clamped only at the start in v
+        let patch = rational_patch();
+        let knots_u: Vec<f64> = (0..9).map(|i| i as f64 / 8.0).collect();
+        let knots_v = vec![0.0, 0.0, 0.0, 0.3, 0.5, 0.7, 0.9];
+        let surface = NURBSSurface::new(3, 2, patch.control_points, patch.weights, knots_u, knots_v);
+
+        let clamped = surface.clamped();
+        assert!(clamped.is_clamped());
+        assert_eq!(clamped.domain(), surface.domain());
+        assert_eq!(clamped.knots_u, vec![0.375, 0.375, 0.375, 0.375, 0.5, 0.625, 0.625, 0.625, 0.625]);
+        assert_eq!(clamped.knots_v, vec![0.0, 0.0, 0.0, 0.3, 0.5, 0.5, 0.5]);
+
+        let ([u0, u1], [v0, v1]) = surface.domain();
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let u = u0 + (u1 - u0) * i as f64 / 10.0;
+                let v = v0 + (v1 - v0) * j as f64 / 10.0;
+                let (a, b) = (surface.evaluate(u, v), clamped.evaluate(u, v));
+                for k in 0..3 {
+                    assert_relative_eq!(a[k], b[k], epsilon = 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_knot_at_unclamped_domain_ends() {
+        let patch = rational_patch();
+        let knots_u: Vec<f64> = (0..9).map(|i| i as f64).collect();
+        let knots_v: Vec<f64> = (0..7).map(|i| i as f64).collect();
+        let surface = NURBSSurface::new(3, 2, patch.control_points, patch.weights, knots_u, knots_v);
+        assert_eq!(surface.domain(), ([3.0, 5.0], [2.0, 4.0]));
+
+        let refined = surface
+            .insert_knot_u(3.0, 1)
+            .insert_knot_u(5.0, 2)
+            .insert_knot_v(2.0, 2)
+            .insert_knot_v(4.0, 1);
+        assert_eq!(refined.knots_u, vec![0.0, 1.0, 2.0, 3.0, 3.0, 4.0, 5.0, 5.0, 5.0, 6.0, 7.0, 8.0]);
+        assert_eq!(refined.knots_v, vec![0.0, 1.0, 2.0, 2.0, 3.0, 4.0, 4.0, 5.0, 6.0]);
+
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let u = 3.0 + 2.0 * i as f64 / 10.0;
+                let v = 2.0 + 2.0 * j as f64 / 10.0;
+                let (a, b) = (surface.evaluate(u, v), refined.evaluate(u, v));
+                for k in 0..3 {
+                    assert_relative_eq!(a[k], b[k], epsilon = 1e-10);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Knot removal and surface simplification
+//!
+//! Removal is attempted one knot occurrence at a time on every control row
+//! of the surface. A candidate is only accepted when the sampled deviation
+//! from the original surface stays within the user tolerance.
+
+use ndarray::Array2;
+
+use crate::refine::knot_multiplicity;
+use crate::surface::{Direction, NURBSSurface};
+use crate::vector;
+
+/// Result of `NURBSSurface::simplify`
+#[derive(Debug, Clone)]
+pub struct Simplification {
+    pub surface: NURBSSurface,
+    pub removed_u: usize,
+    pub removed_v: usize,
+    /// Maximum sampled distance between the original and simplified surface
+    pub max_error: f64,
+}
+
+impl NURBSSurface {
+    /// Remove one occurrence of the interior u knot `t`
+    ///
+    /// Returns `None` if `t` is not an interior knot or if removal would move
+    /// the homogeneous control net by more than the bound derived from
+    /// `tolerance` (NURBS Book, eq. 5.30).
+    pub fn remove_knot_u(&self, t: f64, tolerance: f64) -> Option<NURBSSurface> {
+        let tol = self.homogeneous_tolerance(tolerance);
+        self.try_map_rows(Direction::U, |knots, p, row| {
+            let (k, r) = remove_knot(knots, p, row, t, tol)?;
+            Some((k, p, r))
+        })
+    }
+
+    /// Remove one occurrence of the interior v knot `t`
+    pub fn remove_knot_v(&self, t: f64, tolerance: f64) -> Option<NURBSSurface> {
+        let tol = self.homogeneous_tolerance(tolerance);
+        self.try_map_rows(Direction::V, |knots, q, row| {
+            let (k, r) = remove_knot(knots, q, row, t, tol)?;
+            Some((k, q, r))
+        })
+    }
+
+    /// Remove as many interior knots as possible within `tolerance`
+    ///
+    /// Knots are removed greedily in both directions until no further
+    /// removal keeps the deviation from the original surface below
+    /// `tolerance`.
+    pub fn simplify(&self, tolerance: f64) -> Simplification {
+        let mut current = self.clone();
+        let mut removed_u = 0;
+        let mut removed_v = 0;
+
+        loop {
+            let mut progress = false;
+
+            for dir in [Direction::U, Direction::V] {
+                let (p, knots) = current.basis_in(dir);
+                let n = knots.len() - p - 1;
+                let mut candidates: Vec<f64> = knots[p + 1..n].to_vec();
+                candidates.dedup();
+
+                for t in candidates {
+                    let candidate = match dir {
+                        Direction::U => current.remove_knot_u(t, tolerance),
+                        Direction::V => current.remove_knot_v(t, tolerance),
+                    };
+
+                    let Some(candidate) = candidate else {
+                        continue;
+                    };
+
+                    if max_deviation(self, &candidate) <= tolerance {
+                        current = candidate;
+                        progress = true;
+                        match dir {
+                            Direction::U => removed_u += 1,
+                            Direction::V => removed_v += 1,
+                        }
+                    }
+                }
+            }
+
+            if !progress {
+                break;
+            }
+        }
+
+        Simplification {
+            max_error: max_deviation(self, &current),
+            surface: current,
+            removed_u,
+            removed_v,
+        }
+    }
+
+    /// Translate a Euclidean tolerance into a bound on homogeneous points
+    fn homogeneous_tolerance(&self, tolerance: f64) -> f64 {
+        let w_min = self.weights.iter().cloned().fold(f64::INFINITY, f64::min);
+        let (u_res, v_res) = self.dimensions();
+
+        let mut p_max: f64 = 0.0;
+        for i in 0..u_res {
+            for j in 0..v_res {
+                p_max = p_max.max(vector::norm(&self.control_point(i, j)));
+            }
+        }
+
+        tolerance * w_min / (1.0 + p_max)
+    }
+}
+
+/// Maximum distance between two surfaces over the knot spans of `reference`
+pub(crate) fn max_deviation(reference: &NURBSSurface, other: &NURBSSurface) -> f64 {
+    let us = span_samples(&reference.knots_u, reference.degree_u);
+    let vs = span_samples(&reference.knots_v, reference.degree_v);
+
+    let mut max_error: f64 = 0.0;
+    for &u in &us {
+        for &v in &vs {
+            let a = reference.evaluate(u, v);
+            let b = other.evaluate(u, v);
+            max_error = max_error.max(vector::distance(&a, &b));
+        }
+    }
+
+    max_error
+}
+
+/// Parameter samples covering every non-empty knot span
+fn span_samples(knots: &[f64], degree: usize) -> Vec<f64> {
+    let per_span = degree + 3;
+    let n = knots.len() - degree - 1;
+    let mut samples = vec![knots[degree]];
+
+    for span in degree..n {
+        let (a, b) = (knots[span], knots[span + 1]);
+        if b > a {
+            samples.extend((1..=per_span).map(|k| a + (b - a) * k as f64 / per_span as f64));
+        }
+    }
+
+    samples
+}
+
+/// Remove one occurrence of knot `t` from a curve with homogeneous points
+/// `pw` if the control net changes by at most `tol` (NURBS Book, A5.8)
+pub(crate) fn remove_knot(
+    knots: &[f64],
+    degree: usize,
+    pw: &Array2<f64>,
+    t: f64,
+    tol: f64,
+) -> Option<(Vec<f64>, Array2<f64>)> {
+    let p = degree;
+    let n = pw.shape()[0];
+    let dim = pw.shape()[1];
+    let s = knot_multiplicity(knots, t);
+
+    // Only interior knots can be removed
+    if s == 0 || t <= knots[p] || t >= knots[n] {
+        return None;
+    }
+
+    let r = knots.iter().rposition(|&k| k == t)?;
+    let ord = p + 1;
+    let first = r - p;
+    let last = r - s;
+    let off = first - 1;
+
+    let mut temp = vec![vec![0.0; dim]; last + 2 - off];
+    temp[0] = pw.row(off).to_vec();
+    temp[last + 1 - off] = pw.row(last + 1).to_vec();
+
+    // Signed indices: j may drop below i when s > p - 1
+    let (mut i, mut j) = (first as isize, last as isize);
+    let (mut ii, mut jj) = (1usize, last - off);
+
+    while j - i > 0 {
+        let (iu, ju) = (i as usize, j as usize);
+        let alfi = (t - knots[iu]) / (knots[iu + ord] - knots[iu]);
+        let alfj = (t - knots[ju]) / (knots[ju + ord] - knots[ju]);
+
+        for d in 0..dim {
+            temp[ii][d] = (pw[[iu, d]] - (1.0 - alfi) * temp[ii - 1][d]) / alfi;
+            temp[jj][d] = (pw[[ju, d]] - alfj * temp[jj + 1][d]) / (1.0 - alfj);
+        }
+
+        i += 1;
+        ii += 1;
+        j -= 1;
+        jj -= 1;
+    }
+
+    let error = if j - i < 0 {
+        distance(&temp[ii - 1], &temp[jj + 1])
+    } else {
+        let iu = i as usize;
+        let alfi = (t - knots[iu]) / (knots[iu + ord] - knots[iu]);
+        let blended: Vec<f64> = (0..dim)
+            .map(|d| alfi * temp[ii + 1][d] + (1.0 - alfi) * temp[ii - 1][d])
+            .collect();
+        distance(&pw.row(iu).to_vec(), &blended)
+    };
+
+    if error > tol {
+        return None;
+    }
+
+    // Save new control points
+    let mut qw = pw.clone();
+    let (mut i, mut j) = (first as isize, last as isize);
+    while j - i > 0 {
+        let (iu, ju) = (i as usize, j as usize);
+        for d in 0..dim {
+            qw[[iu, d]] = temp[iu - off][d];
+            qw[[ju, d]] = temp[ju - off][d];
+        }
+        i += 1;
+        j -= 1;
+    }
+
+    // Drop the control point that became redundant
+    let fout = (2 * r - s - p) / 2;
+    let mut result = Array2::zeros((n - 1, dim));
+    for (k, row) in (0..n).filter(|&k| k != fout).enumerate() {
+        result.row_mut(k).assign(&qw.row(row));
+    }
+
+    // Removal must not produce invalid weights
+    if result.column(dim - 1).iter().any(|&w| w <= 0.0) {
+        return None;
+    }
+
+    let mut new_knots = knots.to_vec();
+    new_knots.remove(r);
+
+    Some((new_knots, result))
+}
+
+/// Euclidean distance between homogeneous points
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use crate::fixtures::wavy_patch;
+
+    #[test]
+    fn test_simplify_removes_inserted_knots() {
+        let surface = wavy_patch();
+        let refined = surface.refine_knots_u(&[0.1, 0.6, 0.9]).refine_knots_v(&[0.2, 0.5]);
+
+        let result = refined.simplify(1e-8);
+
+        assert_eq!(result.removed_u, 3);
+        assert_eq!(result.removed_v, 2);
+        assert_eq!(result.surface.knots_u, surface.knots_u);
+        assert_eq!(result.surface.knots_v, surface.knots_v);
+        assert!(result.max_error < 1e-8);
+
+        for (a, b) in result.surface.control_points.iter().zip(surface.control_points.iter()) {
+            assert_relative_eq!(a, b, epsilon = 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_simplify_respects_tolerance() {
+        let surface = wavy_patch();
+
+        // Nothing is redundant at a tight tolerance
+        let tight = surface.simplify(1e-10);
+        assert_eq!(tight.removed_u + tight.removed_v, 0);
+        assert_eq!(tight.max_error, 0.0);
+
+        // Knots that are only nearly redundant go once the tolerance allows it
+        let mut perturbed = surface.refine_knots_u(&[0.1, 0.6, 0.9]);
+        perturbed.control_points[[4, 2, 2]] += 0.01;
+
+        let tolerance = 0.05;
+        let loose = perturbed.simplify(tolerance);
+        assert!(loose.removed_u > 0);
+        assert!(loose.max_error > 0.0 && loose.max_error <= tolerance);
+        assert!(max_deviation(&perturbed, &loose.surface) <= tolerance);
+    }
+
+    #[test]
+    fn test_remove_knot_rejects_end_knots() {
+        let surface = wavy_patch();
+
+        assert!(surface.remove_knot_u(0.0, 1.0).is_none());
+        assert!(surface.remove_knot_v(0.4, 1.0).is_none());
+    }
+}
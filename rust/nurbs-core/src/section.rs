@@ -0,0 +1,476 @@
+//! Plane sections and slicing
+//!
+//! The surface is sampled on a parameter grid that subdivides every knot
+//! span, and each grid cell is split into two triangles. Marching over the
+//! triangles gives the contour topology for a plane; every contour vertex
+//! is then projected onto the exact curve n . S(u, v) = h, and vertices are
+//! inserted between neighbours until each chord is within the chordal
+//! tolerance of the surface contour. The sampled grid is shared by all
+//! heights of a slicing run.
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::bezier::breakpoints;
+use crate::bounds::Aabb;
+use crate::error::NurbsError;
+use crate::surface::NURBSSurface;
+use crate::vector::{distance, dot, norm, normalize, segment_distance};
+
+/// Grid samples per knot span and direction
+const SEED_DIVISIONS: usize = 8;
+
+/// Depth limit of chordal refinement between two seeded vertices
+const MAX_REFINEMENT: usize = 12;
+
+const MAX_ITERATIONS: usize = 30;
+
+/// Distance, relative to the size of all sliced surfaces, within which
+/// ends of open contours are joined
+const JOIN_DISTANCE: f64 = 1e-8;
+
+/// Contour of a plane section
+#[derive(Debug, Clone)]
+pub struct Contour {
+    pub points: Vec<[f64; 3]>,
+    /// Index of the surface each point lies on and its (u, v) parameters
+    pub params: Vec<(usize, [f64; 2])>,
+    /// Whether the contour is a loop; the first point is not repeated
+    pub closed: bool,
+}
+
+/// Contours at one slicing height
+#[derive(Debug, Clone)]
+pub struct Slice {
+    pub height: f64,
+    pub contours: Vec<Contour>,
+}
+
+impl NURBSSurface {
+    /// Contours of the section by the plane through `point` with normal
+    /// `normal`
+    ///
+    /// Chords between consecutive points stay within `tolerance` of the
+    /// surface. Section features smaller than the seed grid (eight samples
+    /// per knot span) may be missed. A zero `normal` gives no contours.
+    /// Fails if `tolerance` is not finite and positive.
+    pub fn section(&self, point: [f64; 3], normal: [f64; 3], tolerance: f64) -> Result<Vec<Contour>, NurbsError> {
+        let height = normalize(&normal).map_or(0.0, |unit| dot(&unit, &point));
+        let mut slices = slice(std::slice::from_ref(self), normal, &[height], tolerance)?;
+        Ok(slices.pop().map_or_else(Vec::new, |slice| slice.contours))
+    }
+}
+
+/// Slice a set of surfaces by the planes n . x = h for every height h
+///
+/// `normal` is normalized first, so heights are distances along it; a zero
+/// `normal` gives slices without contours. Open contours of different
+/// surfaces (or across the seam of one surface) are joined where their
+/// ends coincide up to round-off relative to the size of the surfaces.
+/// Fails if `tolerance` is not finite and positive.
+pub fn slice(
+    surfaces: &[NURBSSurface],
+    normal: [f64; 3],
+    heights: &[f64],
+    tolerance: f64,
+) -> Result<Vec<Slice>, NurbsError> {
+    if !tolerance.is_finite() {
+        return Err(NurbsError::NonFinite { field: "tolerance" });
+    }
+    if tolerance <= 0.0 {
+        return Err(NurbsError::NonPositive { field: "tolerance", value: tolerance });
+    }
+
+    let Some(normal) = normalize(&normal) else {
+        return Ok(heights.iter().map(|&height| Slice { height, contours: Vec::new() }).collect());
+    };
+    let grids: Vec<SeedGrid> = surfaces.iter().map(SeedGrid::new).collect();
+    let bounds = surfaces.iter().fold(Aabb::empty(), |bounds, s| bounds.union(&s.bounds()));
+    let join_distance = if bounds.is_empty() { 0.0 } else { JOIN_DISTANCE * norm(&bounds.size()) };
+
+    let slices = heights
+        .par_iter()
+        .map(|&height| {
+            let pieces: Vec<Contour> = surfaces
+                .iter()
+                .zip(&grids)
+                .enumerate()
+                .flat_map(|(index, (surface, grid))| {
+                    let plane = Plane { normal, height, surface, index };
+                    grid.contours(&plane)
+                        .into_iter()
+                        .map(|(params, closed)| plane.refine(params, closed, tolerance))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            Slice {
+                height,
+                contours: join(pieces, join_distance),
+            }
+        })
+        .collect();
+    Ok(slices)
+}
+
+/// Surface samples on the seed grid
+struct SeedGrid {
+    us: Vec<f64>,
+    vs: Vec<f64>,
+    /// Points indexed [i * vs.len() + j]
+    points: Vec<[f64; 3]>,
+}
+
+/// Grid edge between two vertices, smaller index first
+type EdgeKey = (usize, usize);
+
+impl SeedGrid {
+    fn new(surface: &NURBSSurface) -> Self {
+        let subdivide = |knots: &[f64], degree: usize| -> Vec<f64> {
+            let breaks = breakpoints(knots, degree);
+            let mut values = vec![breaks[0]];
+            for span in breaks.windows(2) {
+                values.extend(
+                    (1..=SEED_DIVISIONS).map(|k| span[0] + (span[1] - span[0]) * k as f64 / SEED_DIVISIONS as f64),
+                );
+            }
+            values
+        };
+
+        let us = subdivide(&surface.knots_u, surface.degree_u);
+        let vs = subdivide(&surface.knots_v, surface.degree_v);
+        let uv: Vec<[f64; 2]> = us.iter().flat_map(|&u| vs.iter().map(move |&v| [u, v])).collect();
+        let points = surface.evaluate_batch(&uv);
+        Self { us, vs, points }
+    }
+
+    fn params(&self, vertex: usize) -> [f64; 2] {
+        [self.us[vertex / self.vs.len()], self.vs[vertex % self.vs.len()]]
+    }
+
+    /// Contour topology by marching triangles, as chains of (u, v) seeds
+    fn contours(&self, plane: &Plane) -> Vec<(Vec<[f64; 2]>, bool)> {
+        let nv = self.vs.len();
+        // Values within round-off of the plane are snapped onto it
+        let extent = self.points.iter().flatten().map(|c| c.abs()).fold(0.0, f64::max);
+        let snap = 1e-12 * (extent + plane.height.abs());
+        let values: Vec<f64> = self
+            .points
+            .iter()
+            .map(|p| dot(&plane.normal, p) - plane.height)
+            .map(|f| if f.abs() <= snap { 0.0 } else { f })
+            .collect();
+
+        // Each crossed triangle contributes a segment between two edges;
+        // vertices on the plane count as above it
+        let mut segments: Vec<[EdgeKey; 2]> = Vec::new();
+        for i in 0..self.us.len() - 1 {
+            for j in 0..nv - 1 {
+                let (a, b, c, d) = (i * nv + j, (i + 1) * nv + j, (i + 1) * nv + j + 1, i * nv + j + 1);
+                for triangle in [[a, b, c], [a, c, d]] {
+                    let crossed: Vec<EdgeKey> = [(0, 1), (1, 2), (2, 0)]
+                        .iter()
+                        .map(|&(x, y)| (triangle[x], triangle[y]))
+                        .filter(|&(x, y)| (values[x] >= 0.0) != (values[y] >= 0.0))
+                        .map(|(x, y)| (x.min(y), x.max(y)))
+                        .collect();
+                    if let [first, second] = crossed[..] {
+                        segments.push([first, second]);
+                    }
+                }
+            }
+        }
+
+        let mut by_edge: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+        for (index, segment) in segments.iter().enumerate() {
+            for &edge in segment {
+                by_edge.entry(edge).or_default().push(index);
+            }
+        }
+
+        // Walk the segments into chains, first forwards then backwards
+        let mut used = vec![false; segments.len()];
+        let mut chains = Vec::new();
+        for start in 0..segments.len() {
+            if used[start] {
+                continue;
+            }
+            used[start] = true;
+
+            let mut edges = vec![segments[start][0], segments[start][1]];
+            let mut closed = false;
+            for backwards in [false, true] {
+                loop {
+                    let end = if backwards { edges[0] } else { edges[edges.len() - 1] };
+                    let next = by_edge[&end].iter().copied().find(|&s| !used[s]);
+                    let Some(next) = next else {
+                        break;
+                    };
+                    used[next] = true;
+
+                    let other = if segments[next][0] == end { segments[next][1] } else { segments[next][0] };
+                    if other == edges[0] && !backwards || other == edges[edges.len() - 1] && backwards {
+                        closed = true;
+                        break;
+                    }
+                    if backwards {
+                        edges.insert(0, other);
+                    } else {
+                        edges.push(other);
+                    }
+                }
+                if closed {
+                    break;
+                }
+            }
+
+            let params = edges
+                .iter()
+                .map(|&(x, y)| {
+                    let t = values[x] / (values[x] - values[y]);
+                    let (a, b) = (self.params(x), self.params(y));
+                    [a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])]
+                })
+                .collect();
+            chains.push((params, closed));
+        }
+        chains
+    }
+}
+
+/// Section plane n . x = h on one surface
+struct Plane<'a> {
+    normal: [f64; 3],
+    height: f64,
+    surface: &'a NURBSSurface,
+    index: usize,
+}
+
+impl Plane<'_> {
+    /// Project seeds onto the exact contour and refine chords to `tolerance`
+    fn refine(&self, seeds: Vec<[f64; 2]>, closed: bool, tolerance: f64) -> Contour {
+        let projected: Vec<([f64; 2], [f64; 3])> = seeds.into_iter().map(|uv| self.project(uv)).collect();
+
+        let mut nodes = Vec::with_capacity(projected.len());
+        let count = projected.len();
+        let chords = if closed { count } else { count.saturating_sub(1) };
+        for k in 0..count {
+            nodes.push(projected[k]);
+            if k < chords {
+                self.subdivide(projected[k], projected[(k + 1) % count], tolerance, 0, &mut nodes);
+            }
+        }
+
+        Contour {
+            points: nodes.iter().map(|n| n.1).collect(),
+            params: nodes.iter().map(|n| (self.index, n.0)).collect(),
+            closed,
+        }
+    }
+
+    /// Append the vertices needed between `a` and `b`, in order
+    fn subdivide(
+        &self,
+        a: ([f64; 2], [f64; 3]),
+        b: ([f64; 2], [f64; 3]),
+        tolerance: f64,
+        depth: usize,
+        nodes: &mut Vec<([f64; 2], [f64; 3])>,
+    ) {
+        if depth >= MAX_REFINEMENT {
+            return;
+        }
+        let mid = self.project([0.5 * (a.0[0] + b.0[0]), 0.5 * (a.0[1] + b.0[1])]);
+        if segment_distance(&mid.1, &a.1, &b.1) <= tolerance {
+            return;
+        }
+        self.subdivide(a, mid, tolerance, depth + 1, nodes);
+        nodes.push(mid);
+        self.subdivide(mid, b, tolerance, depth + 1, nodes);
+    }
+
+    /// Newton iteration onto n . S(u, v) = h with minimum-norm steps
+    fn project(&self, [mut u, mut v]: [f64; 2]) -> ([f64; 2], [f64; 3]) {
+        let ([u0, u1], [v0, v1]) = self.surface.domain();
+        for _ in 0..MAX_ITERATIONS {
+            let ders = self.surface.derivatives(u, v, 1);
+            let g = dot(&self.normal, &ders[0][0]) - self.height;
+            let (gu, gv) = (dot(&self.normal, &ders[1][0]), dot(&self.normal, &ders[0][1]));
+            let gradient = gu * gu + gv * gv;
+            if g.abs() <= 1e-13 * (1.0 + self.height.abs()) || gradient == 0.0 {
+                break;
+            }
+            u = (u - g * gu / gradient).clamp(u0, u1);
+            v = (v - g * gv / gradient).clamp(v0, v1);
+        }
+        ([u, v], self.surface.evaluate(u, v))
+    }
+}
+
+/// Join open contours whose ends meet within `reach`, closing chains that
+/// meet themselves
+fn join(contours: Vec<Contour>, reach: f64) -> Vec<Contour> {
+    let (mut result, mut open): (Vec<Contour>, Vec<Contour>) = contours.into_iter().partition(|c| c.closed);
+    let meets = |a: &[f64; 3], b: &[f64; 3]| distance(a, b) <= reach;
+
+    while let Some(mut chain) = open.pop() {
+        loop {
+            let (first, last) = (chain.points[0], chain.points[chain.points.len() - 1]);
+            let next = open.iter().position(|c| {
+                let (a, b) = (&c.points[0], &c.points[c.points.len() - 1]);
+                meets(&last, a) || meets(&last, b) || meets(&first, a) || meets(&first, b)
+            });
+            let Some(index) = next else {
+                break;
+            };
+
+            let mut piece = open.swap_remove(index);
+            if meets(&last, &piece.points[piece.points.len() - 1]) || meets(&first, &piece.points[0]) {
+                piece.points.reverse();
+                piece.params.reverse();
+            }
+            if meets(&last, &piece.points[0]) {
+                chain.points.extend(piece.points.into_iter().skip(1));
+                chain.params.extend(piece.params.into_iter().skip(1));
+            } else {
+                piece.points.extend(chain.points.into_iter().skip(1));
+                piece.params.extend(chain.params.into_iter().skip(1));
+                chain.points = piece.points;
+                chain.params = piece.params;
+            }
+        }
+
+        let count = chain.points.len();
+        if count > 2 && meets(&chain.points[0], &chain.points[count - 1]) {
+            chain.points.pop();
+            chain.params.pop();
+            chain.closed = true;
+        }
+        result.push(chain);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::{add, scale};
+    use approx::assert_relative_eq;
+
+    /// Largest distance from chord midpoints of a contour to a circle
+    /// about the z axis
+    fn sagitta(contour: &Contour, radius: f64) -> f64 {
+        let n = contour.points.len();
+        (0..n)
+            .map(|k| {
+                let (a, b) = (contour.points[k], contour.points[(k + 1) % n]);
+                let mid = scale(&add(&a, &b), 0.5);
+                radius - mid[0].hypot(mid[1])
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_sphere_section_is_closed_and_within_tolerance() {
+        let sphere = NURBSSurface::sphere([0.0; 3], 1.0);
+        let contours = sphere.section([0.0, 0.0, 0.5], [0.0, 0.0, 2.0], 1e-4).unwrap();
+        assert_eq!(contours.len(), 1);
+
+        let contour = &contours[0];
+        assert!(contour.closed);
+        let radius = 0.75f64.sqrt();
+        for (p, (surface, uv)) in contour.points.iter().zip(&contour.params) {
+            assert_eq!(*surface, 0);
+            assert_relative_eq!(p[2], 0.5, epsilon = 1e-12);
+            assert_relative_eq!(p[0].hypot(p[1]), radius, epsilon = 1e-12);
+            assert_relative_eq!(distance(p, &sphere.evaluate(uv[0], uv[1])), 0.0, epsilon = 1e-12);
+        }
+        assert!(sagitta(contour, radius) <= 1e-4);
+    }
+
+    #[test]
+    fn test_cylinder_slices_and_open_contours() {
+        let cylinder = NURBSSurface::cylinder([0.0; 3], [0.0, 0.0, 1.0], 1.0, 3.0);
+        let slices = slice(std::slice::from_ref(&cylinder), [0.0, 0.0, 1.0], &[0.5, 1.0, 2.5, 4.0], 1e-3).unwrap();
+
+        assert_eq!(slices.len(), 4);
+        for slice in &slices[..3] {
+            assert_eq!(slice.contours.len(), 1);
+            assert!(slice.contours[0].closed);
+            for p in &slice.contours[0].points {
+                assert_relative_eq!(p[2], slice.height, epsilon = 1e-12);
+            }
+            assert!(sagitta(&slice.contours[0], 1.0) <= 1e-3);
+        }
+        assert!(slices[3].contours.is_empty());
+
+        // Lengthwise: two straight open rulings at x = 0.5
+        let contours = cylinder.section([0.5, 0.0, 0.0], [1.0, 0.0, 0.0], 1e-3).unwrap();
+        assert_eq!(contours.len(), 2);
+        for contour in &contours {
+            assert!(!contour.closed);
+            let (first, last) = (contour.points[0], contour.points[contour.points.len() - 1]);
+            assert_relative_eq!((first[2] - last[2]).abs(), 3.0, epsilon = 1e-12);
+            assert_relative_eq!(first[1].abs(), 0.75f64.sqrt(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_contours_join_across_surfaces() {
+        // A cylinder in two halves slices into whole circles
+        let cylinder = NURBSSurface::cylinder([0.0; 3], [0.0, 0.0, 1.0], 2.0, 1.0);
+        let (left, right) = cylinder.split_u(0.4);
+        let slices = slice(&[left, right], [0.0, 0.0, 1.0], &[0.25, 0.75], 1e-3).unwrap();
+        for slice in &slices {
+            assert_eq!(slice.contours.len(), 1);
+            let contour = &slice.contours[0];
+            assert!(contour.closed);
+            assert!(contour.params.iter().any(|p| p.0 == 0) && contour.params.iter().any(|p| p.0 == 1));
+        }
+
+        // Through the axis of a torus: the two tube circles
+        let torus = NURBSSurface::torus([0.0; 3], [0.0, 0.0, 1.0], 3.0, 1.0);
+        let contours = torus.section([0.0; 3], [0.0, 1.0, 0.0], 1e-4).unwrap();
+        assert_eq!(contours.len(), 2);
+        for contour in &contours {
+            assert!(contour.closed);
+            let center = [3.0 * contour.points[0][0].signum(), 0.0, 0.0];
+            for p in &contour.points {
+                assert_relative_eq!(distance(p, &center), 1.0, epsilon = 1e-12);
+            }
+        }
+
+        // Ends closer than a loose chordal tolerance stay apart
+        let near = [
+            NURBSSurface::plane([0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            NURBSSurface::plane([1.05, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ];
+        let slices = slice(&near, [0.0, 1.0, 0.0], &[0.5], 0.1).unwrap();
+        assert_eq!(slices[0].contours.len(), 2);
+        assert!(slices[0].contours.iter().all(|c| !c.closed));
+
+        assert!(torus.section([0.0; 3], [0.0; 3], 1e-4).unwrap().is_empty());
+        let slices = slice(&near, [0.0; 3], &[0.5, 1.0], 1e-4).unwrap();
+        assert_eq!(slices.len(), 2);
+        assert!(slices.iter().all(|s| s.contours.is_empty()));
+    }
+
+    #[test]
+    fn test_invalid_tolerance() {
+        let sphere = NURBSSurface::sphere([0.0; 3], 1.0);
+        for tolerance in [0.0, -1e-3] {
+            assert_eq!(
+                sphere.section([0.0; 3], [0.0, 0.0, 1.0], tolerance).unwrap_err(),
+                NurbsError::NonPositive { field: "tolerance", value: tolerance }
+            );
+        }
+        assert_eq!(
+            slice(std::slice::from_ref(&sphere), [0.0, 0.0, 1.0], &[0.0], f64::NAN).unwrap_err(),
+            NurbsError::NonFinite { field: "tolerance" }
+        );
+
+        // Checked even when a zero normal gives no contours
+        assert!(sphere.section([0.0; 3], [0.0; 3], 0.0).is_err());
+    }
+}
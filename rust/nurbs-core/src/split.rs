@@ -0,0 +1,204 @@
+//! Splitting and cropping surfaces at parameter values
+//!
+//! The split knot is inserted until its multiplicity reaches the degree, at
+//! which point the control net separates into two independent nets sharing
+//! the boundary row. Regions are extracted by up to two splits per direction.
+
+use ndarray::{s, Array2, Array3};
+
+use crate::surface::{Direction, NURBSSurface};
+
+impl NURBSSurface {
+    /// Split at u = `t` into the parts over [u_min, t] and [t, u_max]
+    ///
+    /// Both parts keep their original parameter intervals, so each evaluates
+    /// identically to the surface on its half.
+    ///
+    /// # Panics
+    /// Panics if `t` is not strictly inside the u domain.
+    pub fn split_u(&self, t: f64) -> (NURBSSurface, NURBSSurface) {
+        self.split(Direction::U, t)
+    }
+
+    /// Split at v = `t` into the parts over [v_min, t] and [t, v_max]
+    ///
+    /// # Panics
+    /// Panics if `t` is not strictly inside the v domain.
+    pub fn split_v(&self, t: f64) -> (NURBSSurface, NURBSSurface) {
+        self.split(Direction::V, t)
+    }
+
+    /// Exact sub-surface over [u0, u1] x [v0, v1]
+    ///
+    /// The result keeps the original parameters; chain `reparameterize` to
+    /// map it onto [0, 1]^2:
+    /// `surface.extract_region(u, v).reparameterize([0.0, 1.0], [0.0, 1.0])`.
+    ///
+    /// # Panics
+    /// Panics if a range is empty or not contained in the surface domain.
+    pub fn extract_region(&self, u_range: [f64; 2], v_range: [f64; 2]) -> NURBSSurface {
+        let crop_u = self.crop(Direction::U, u_range);
+        crop_u.crop(Direction::V, v_range)
+    }
+
+    /// Part of the surface within `range` in direction `dir`
+    fn crop(&self, dir: Direction, range: [f64; 2]) -> NURBSSurface {
+        let (p, knots) = self.basis_in(dir);
+        let n = knots.len() - p - 1;
+        let [t0, t1] = range;
+        assert!(
+            knots[p] <= t0 && t0 < t1 && t1 <= knots[n],
+            "region [{}, {}] is empty or outside the domain [{}, {}]",
+            t0,
+            t1,
+            knots[p],
+            knots[n]
+        );
+
+        let upper = if t0 > knots[p] { self.split(dir, t0).1 } else { self.clone() };
+        if t1 < knots[n] {
+            upper.split(dir, t1).0
+        } else {
+            upper
+        }
+    }
+
+    fn split(&self, dir: Direction, t: f64) -> (NURBSSurface, NURBSSurface) {
+        let (p, knots) = self.basis_in(dir);
+        let n = knots.len() - p - 1;
+        assert!(
+            knots[p] < t && t < knots[n],
+            "split parameter {} outside the open domain ({}, {})",
+            t,
+            knots[p],
+            knots[n]
+        );
+
+        let refined = match dir {
+            Direction::U => self.insert_knot_u(t, p),
+            Direction::V => self.insert_knot_v(t, p),
+        };
+        let (_, knots) = refined.basis_in(dir);
+
+        // t now occupies knots[first..=last] with multiplicity p or p + 1
+        let first = knots.iter().position(|&k| k == t).unwrap();
+        let last = knots.iter().rposition(|&k| k == t).unwrap();
+
+        let left_knots = [&knots[..first], &vec![t; p + 1]].concat();
+        let right_knots = [&vec![t; p + 1], &knots[last + 1..]].concat();
+
+        let left = refined.slice_control_net(dir, 0, first);
+        let right = refined.slice_control_net(dir, last - p, knots.len() - p - 1);
+
+        match dir {
+            Direction::U => (
+                NURBSSurface::new(p, self.degree_v, left.0, left.1, left_knots, self.knots_v.clone()),
+                NURBSSurface::new(p, self.degree_v, right.0, right.1, right_knots, self.knots_v.clone()),
+            ),
+            Direction::V => (
+                NURBSSurface::new(self.degree_u, p, left.0, left.1, self.knots_u.clone(), left_knots),
+                NURBSSurface::new(self.degree_u, p, right.0, right.1, self.knots_u.clone(), right_knots),
+            ),
+        }
+    }
+
+    /// Control points and weights with indices start..end in direction `dir`
+    fn slice_control_net(&self, dir: Direction, start: usize, end: usize) -> (Array3<f64>, Array2<f64>) {
+        match dir {
+            Direction::U => (
+                self.control_points.slice(s![start..end, .., ..]).to_owned(),
+                self.weights.slice(s![start..end, ..]).to_owned(),
+            ),
+            Direction::V => (
+                self.control_points.slice(s![.., start..end, ..]).to_owned(),
+                self.weights.slice(s![.., start..end]).to_owned(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::wavy_patch;
+    use approx::assert_relative_eq;
+
+    fn assert_reproduces(original: &NURBSSurface, part: &NURBSSurface) {
+        let ([u0, u1], [v0, v1]) = part.domain();
+        for a in 0..=6 {
+            for b in 0..=6 {
+                let u = u0 + (u1 - u0) * a as f64 / 6.0;
+                let v = v0 + (v1 - v0) * b as f64 / 6.0;
+
+                let expected = original.evaluate(u, v);
+                let actual = part.evaluate(u, v);
+                for k in 0..3 {
+                    assert_relative_eq!(expected[k], actual[k], epsilon = 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_reproduces_halves() {
+        let surface = wavy_patch();
+
+        let (left, right) = surface.split_u(0.55);
+        assert_eq!(left.domain().0, [0.0, 0.55]);
+        assert_eq!(right.domain().0, [0.55, 1.0]);
+        assert!(left.is_clamped() && right.is_clamped());
+        assert_reproduces(&surface, &left);
+        assert_reproduces(&surface, &right);
+
+        let (lower, upper) = surface.split_v(0.6);
+        assert_eq!(lower.domain().1, [0.0, 0.6]);
+        assert_eq!(upper.domain().1, [0.6, 1.0]);
+        assert_reproduces(&surface, &lower);
+        assert_reproduces(&surface, &upper);
+
+        // Boundary rows coincide
+        for j in 0..6 {
+            assert_eq!(left.control_point(left.dimensions().0 - 1, j), right.control_point(0, j));
+        }
+    }
+
+    #[test]
+    fn test_split_at_existing_knot() {
+        let surface = wavy_patch();
+
+        let (left, right) = surface.split_u(0.5);
+        assert_eq!(left.knots_u, vec![0.0, 0.0, 0.0, 0.0, 0.25, 0.5, 0.5, 0.5, 0.5]);
+        assert_eq!(right.knots_u, vec![0.5, 0.5, 0.5, 0.5, 0.75, 1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(left.dimensions().0 + right.dimensions().0, surface.dimensions().0 + 3);
+        assert_reproduces(&surface, &left);
+        assert_reproduces(&surface, &right);
+    }
+
+    #[test]
+    fn test_extract_region() {
+        let surface = wavy_patch();
+
+        let region = surface.extract_region([0.2, 0.7], [0.25, 0.95]);
+        assert_eq!(region.domain(), ([0.2, 0.7], [0.25, 0.95]));
+        assert_reproduces(&surface, &region);
+
+        // Ranges touching the domain ends need only one split
+        let edge = surface.extract_region([0.0, 0.3], [0.5, 1.0]);
+        assert_eq!(edge.domain(), ([0.0, 0.3], [0.5, 1.0]));
+        assert_reproduces(&surface, &edge);
+
+        let unit = region.reparameterize([0.0, 1.0], [0.0, 1.0]);
+        assert_eq!(unit.domain(), ([0.0, 1.0], [0.0, 1.0]));
+        let expected = surface.evaluate(0.45, 0.6);
+        let actual = unit.evaluate(0.5, 0.5);
+        for k in 0..3 {
+            assert_relative_eq!(expected[k], actual[k], epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the open domain")]
+    fn test_split_rejects_domain_end() {
+        wavy_patch().split_u(1.0);
+    }
+}
@@ -0,0 +1,582 @@
+//! Surface–surface intersection
+//!
+//! Pairs of Bezier patches whose boxes overlap are subdivided until both
+//! are flat; each remaining pair seeds a minimum-norm Newton iteration onto
+//! the intersection. Branches are traced from the seeds by marching along
+//! N_a x N_b: every predicted step is corrected back onto both surfaces in
+//! the plane normal to the tangent, and steps that leave a parameter domain
+//! are clipped to the boundary. Seams of closed surfaces are crossed by
+//! continuing from the opposite boundary, so loops around a cylinder or a
+//! sphere come out closed.
+
+use nalgebra::{Matrix3x4, Matrix4, Vector3, Vector4};
+use ndarray::Array2;
+
+use crate::curve::NURBSCurve;
+use crate::error::NurbsError;
+use crate::fitting::Parameterization;
+use crate::intersect::bilinear_deviation;
+use crate::surface::NURBSSurface;
+use crate::vector::{add, cross, distance, dot, norm, normalize, scale, segment_distance, sub};
+
+/// Deviation from the bilinear corner patch, relative to the size of the
+/// whole surface, below which a patch pair seeds Newton iteration
+const FLATNESS: f64 = 1e-2;
+
+/// Subdivision depth limit per patch
+const MAX_DEPTH: usize = 8;
+
+const MAX_ITERATIONS: usize = 20;
+
+/// Largest tangent turn per marching step (radians)
+const MAX_TURN: f64 = 0.2;
+
+/// Smallest step, relative to `IntersectionOptions::step`, before a branch
+/// is given up (at tangential contacts)
+const MIN_STEP: f64 = 1e-4;
+
+/// Upper bound on the points of one branch
+const MAX_POINTS: usize = 100_000;
+
+/// Settings for `NURBSSurface::intersect_surface`
+#[derive(Debug, Clone)]
+pub struct IntersectionOptions {
+    /// Marching step: largest advance along the tangent between
+    /// consecutive traced points
+    pub step: f64,
+    /// Largest distance between the two surfaces at a traced point
+    pub tolerance: f64,
+    /// Fit each branch of more than one point with a B-spline curve
+    /// through its points within this distance
+    pub fit_tolerance: Option<f64>,
+}
+
+impl IntersectionOptions {
+    /// Marching with the given step, tracing to 1e-10 without curve fitting
+    pub fn new(step: f64) -> Self {
+        Self {
+            step,
+            tolerance: 1e-10,
+            fit_tolerance: None,
+        }
+    }
+}
+
+/// Connected piece of the intersection of two surfaces
+#[derive(Debug, Clone)]
+pub struct IntersectionBranch {
+    pub points: Vec<[f64; 3]>,
+    /// Parameters of each point on the first and second surface; they jump
+    /// where the branch crosses the seam of a closed surface
+    pub params_a: Vec<[f64; 2]>,
+    pub params_b: Vec<[f64; 2]>,
+    /// Whether the branch is a loop; the first point is not repeated
+    pub closed: bool,
+    /// Cubic fitted to the points, if `fit_tolerance` is set and the
+    /// branch has more than one point
+    pub curve: Option<NURBSCurve>,
+}
+
+/// Extra equation closing the 3 x 4 system S_a(u, v) = S_b(s, t)
+#[derive(Debug, Clone, Copy)]
+enum Constraint {
+    /// Minimum-norm Newton steps on the underdetermined system
+    None,
+    /// Point in the plane through `point` normal to `normal`
+    Plane { point: [f64; 3], normal: [f64; 3] },
+    /// Parameter `index` of (u, v, s, t) held at `value`
+    Fixed { index: usize, value: f64 },
+}
+
+/// Why marching in one direction stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    Closed,
+    Open,
+}
+
+/// Traced point with its (u, v, s, t) parameters
+type Node = ([f64; 3], [f64; 4]);
+
+/// A pair of surfaces with their domains as (u, v, s, t) bounds
+struct Pair<'a> {
+    a: &'a NURBSSurface,
+    b: &'a NURBSSurface,
+    bounds: [[f64; 2]; 4],
+    /// Whether each parameter runs around a seam
+    periodic: [bool; 4],
+    options: &'a IntersectionOptions,
+}
+
+impl NURBSSurface {
+    /// All intersection branches of `self` and `other`
+    ///
+    /// Branches closer to each other than half a step may be traced as one.
+    /// Intersections through degenerate points (where S_u x S_v vanishes)
+    /// or along tangential contacts end the branch there.
+    ///
+    /// Fails if `step`, `tolerance` or `fit_tolerance` is not finite and
+    /// positive, or if fitting a branch fails.
+    pub fn intersect_surface(
+        &self,
+        other: &NURBSSurface,
+        options: &IntersectionOptions,
+    ) -> Result<Vec<IntersectionBranch>, NurbsError> {
+        let settings = [
+            ("step", Some(options.step)),
+            ("tolerance", Some(options.tolerance)),
+            ("fit tolerance", options.fit_tolerance),
+        ];
+        for (field, value) in settings {
+            match value {
+                Some(value) if !value.is_finite() => return Err(NurbsError::NonFinite { field }),
+                Some(value) if value <= 0.0 => return Err(NurbsError::NonPositive { field, value }),
+                _ => {}
+            }
+        }
+
+        let (ua, va) = self.domain();
+        let (ub, vb) = other.domain();
+        let pair = Pair {
+            a: self,
+            b: other,
+            bounds: [ua, va, ub, vb],
+            periodic: [
+                is_periodic(self, true),
+                is_periodic(self, false),
+                is_periodic(other, true),
+                is_periodic(other, false),
+            ],
+            options,
+        };
+
+        let mut seeds = pair.seeds();
+        let mut branches: Vec<IntersectionBranch> = Vec::new();
+        while let Some(seed) = seeds.pop() {
+            let Some(branch) = pair.trace(seed) else {
+                continue;
+            };
+
+            // Drop the seeds this branch passes through
+            let reach = 0.5 * options.step;
+            let segments = branch.points.len() - 1 + usize::from(branch.closed);
+            seeds.retain(|&(p, _)| {
+                (0..segments).all(|k| {
+                    let (a, b) = (branch.points[k], branch.points[(k + 1) % branch.points.len()]);
+                    segment_distance(&p, &a, &b) > reach
+                }) && (segments > 0 || distance(&p, &branch.points[0]) > reach)
+            });
+            branches.push(branch);
+        }
+
+        if let Some(tolerance) = options.fit_tolerance {
+            for branch in branches.iter_mut().filter(|branch| branch.points.len() > 1) {
+                branch.curve = Some(fit_branch(&branch.points, branch.closed, tolerance)?);
+            }
+        }
+        Ok(branches)
+    }
+}
+
+impl Pair<'_> {
+    /// Points of the intersection found from flat patch pairs
+    fn seeds(&self) -> Vec<Node> {
+        let size_a = norm(&self.a.bounds().size());
+        let size_b = norm(&self.b.bounds().size());
+        let margin = self.options.tolerance;
+
+        let mut stack: Vec<(NURBSSurface, NURBSSurface, usize)> = Vec::new();
+        for patch_a in self.a.to_bezier_patches().into_iter().flatten() {
+            for patch_b in self.b.to_bezier_patches().into_iter().flatten() {
+                stack.push((patch_a.surface.clone(), patch_b.surface, 0));
+            }
+        }
+
+        let mut seeds = Vec::new();
+        while let Some((pa, pb, depth)) = stack.pop() {
+            if !pa.bounds().expand(margin).intersects(&pb.bounds()) {
+                continue;
+            }
+
+            let flat_a = bilinear_deviation(&pa) <= FLATNESS * size_a;
+            let flat_b = bilinear_deviation(&pb) <= FLATNESS * size_b;
+            if depth < MAX_DEPTH && !(flat_a && flat_b) {
+                for qa in if flat_a { vec![pa.clone()] } else { quarters(&pa) } {
+                    for qb in if flat_b { vec![pb.clone()] } else { quarters(&pb) } {
+                        stack.push((qa.clone(), qb, depth + 1));
+                    }
+                }
+                continue;
+            }
+
+            let ([u0, u1], [v0, v1]) = pa.domain();
+            let ([s0, s1], [t0, t1]) = pb.domain();
+            let start = [0.5 * (u0 + u1), 0.5 * (v0 + v1), 0.5 * (s0 + s1), 0.5 * (t0 + t1)];
+            if let Some(x) = self.solve(start, Constraint::None) {
+                seeds.push((self.point(x), x));
+            }
+        }
+        seeds
+    }
+
+    /// Trace the branch through `seed` in both directions
+    fn trace(&self, seed: Node) -> Option<IntersectionBranch> {
+        let (forward, end) = self.march(seed.1, 1.0)?;
+        let mut nodes = if end == End::Closed {
+            forward
+        } else {
+            let (backward, _) = self.march(seed.1, -1.0)?;
+            backward.into_iter().rev().chain(forward.into_iter().skip(1)).collect()
+        };
+
+        // Open branches whose ends meet across a seam are loops too
+        let mut closed = end == End::Closed;
+        if !closed && nodes.len() > 2 {
+            let (first, last) = (nodes[0].0, nodes[nodes.len() - 1].0);
+            if distance(&first, &last) <= 1e3 * self.options.tolerance {
+                nodes.pop();
+                closed = true;
+            }
+        }
+
+        Some(IntersectionBranch {
+            points: nodes.iter().map(|n| n.0).collect(),
+            params_a: nodes.iter().map(|n| [n.1[0], n.1[1]]).collect(),
+            params_b: nodes.iter().map(|n| [n.1[2], n.1[3]]).collect(),
+            closed,
+            curve: None,
+        })
+    }
+
+    /// March from `start` along `sign` * (N_a x N_b) until the branch
+    /// leaves a domain, returns to its start or stalls
+    fn march(&self, start: [f64; 4], sign: f64) -> Option<(Vec<Node>, End)> {
+        let origin = self.point(start);
+        let mut nodes = vec![(origin, start)];
+        let (mut x, mut previous) = (start, None);
+        let mut step = self.options.step;
+        let mut travelled = 0.0;
+
+        // Marching cannot start at a tangential contact
+        self.tangent(start)?;
+
+        while nodes.len() < MAX_POINTS {
+            let Some((mut tangent, mut dx)) = self.tangent(x) else {
+                return Some((nodes, End::Open));
+            };
+            let orientation = match previous {
+                Some(t) => dot(&tangent, &t).signum(),
+                None => sign,
+            };
+            tangent = scale(&tangent, orientation);
+            dx = dx.map(|c| c * orientation);
+
+            let p = nodes[nodes.len() - 1].0;
+            let (next, boundary) = match self.exit(x, dx, step) {
+                Some((index, alpha)) => {
+                    let guess = self.clamp([0, 1, 2, 3].map(|k| x[k] + alpha * step * dx[k]));
+                    let value = guess[index];
+                    (self.solve(guess, Constraint::Fixed { index, value }), Some(index))
+                }
+                None => {
+                    let guess = self.clamp([0, 1, 2, 3].map(|k| x[k] + step * dx[k]));
+                    let point = add(&p, &scale(&tangent, step));
+                    (self.solve(guess, Constraint::Plane { point, normal: tangent }), None)
+                }
+            };
+
+            // Reject corrections that jump away or turn too sharply
+            let accepted = next.filter(|&y| {
+                let q = self.point(y);
+                distance(&p, &q) <= 2.0 * step
+                    && self.tangent(y).is_some_and(|(t, _)| dot(&t, &tangent).abs() >= MAX_TURN.cos())
+            });
+            let Some(y) = accepted else {
+                step *= 0.5;
+                if step < MIN_STEP * self.options.step {
+                    return Some((nodes, End::Open));
+                }
+                continue;
+            };
+
+            let q = self.point(y);
+            travelled += distance(&p, &q);
+            let returned = segment_distance(&origin, &p, &q) <= 0.25 * step;
+            if nodes.len() > 2 && travelled > 2.0 * self.options.step && returned {
+                return Some((nodes, End::Closed));
+            }
+
+            if distance(&p, &q) > 1e3 * self.options.tolerance || boundary.is_none() {
+                nodes.push((q, y));
+            }
+            previous = Some(tangent);
+            x = y;
+            step = (1.5 * step).min(self.options.step);
+
+            if let Some(index) = boundary {
+                if !self.periodic[index] {
+                    return Some((nodes, End::Open));
+                }
+                // Continue from the other side of the seam
+                let [t0, t1] = self.bounds[index];
+                x[index] = if x[index] - t0 < t1 - x[index] { t1 } else { t0 };
+            }
+        }
+        Some((nodes, End::Open))
+    }
+
+    /// Unit tangent N_a x N_b and the matching parameter velocity
+    fn tangent(&self, x: [f64; 4]) -> Option<([f64; 3], [f64; 4])> {
+        let da = self.a.derivatives(x[0], x[1], 1);
+        let db = self.b.derivatives(x[2], x[3], 1);
+        let normal_a = normalize(&cross(&da[1][0], &da[0][1]))?;
+        let normal_b = normalize(&cross(&db[1][0], &db[0][1]))?;
+        let tangent = normalize(&cross(&normal_a, &normal_b))?;
+
+        let [du, dv] = tangent_parameters(&da[1][0], &da[0][1], &tangent)?;
+        let [ds, dt] = tangent_parameters(&db[1][0], &db[0][1], &tangent)?;
+        Some((tangent, [du, dv, ds, dt]))
+    }
+
+    /// First parameter to leave its domain within `step` along `dx`, as
+    /// (index, fraction of the step)
+    fn exit(&self, x: [f64; 4], dx: [f64; 4], step: f64) -> Option<(usize, f64)> {
+        (0..4)
+            .filter_map(|k| {
+                let [t0, t1] = self.bounds[k];
+                let target = x[k] + step * dx[k];
+                let bound = if target < t0 {
+                    t0
+                } else if target > t1 {
+                    t1
+                } else {
+                    return None;
+                };
+                Some((k, ((bound - x[k]) / (step * dx[k])).clamp(0.0, 1.0)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Newton iteration onto S_a = S_b under `constraint`
+    fn solve(&self, mut x: [f64; 4], constraint: Constraint) -> Option<[f64; 4]> {
+        for _ in 0..MAX_ITERATIONS {
+            let da = self.a.derivatives(x[0], x[1], 1);
+            let db = self.b.derivatives(x[2], x[3], 1);
+            let f = sub(&da[0][0], &db[0][0]);
+
+            let extra = match constraint {
+                Constraint::None => 0.0,
+                Constraint::Plane { point, normal } => dot(&normal, &sub(&da[0][0], &point)),
+                Constraint::Fixed { index, value } => x[index] - value,
+            };
+            if norm(&f) <= self.options.tolerance && extra.abs() <= self.options.tolerance {
+                return Some(x);
+            }
+
+            let columns = [da[1][0], da[0][1], scale(&db[1][0], -1.0), scale(&db[0][1], -1.0)];
+            let jacobian = Matrix3x4::from_fn(|r, c| columns[c][r]);
+            let residual = Vector3::from(f);
+
+            // Fourth row of the Jacobian for square systems
+            let row = match constraint {
+                Constraint::None => None,
+                Constraint::Plane { normal, .. } => {
+                    Some([dot(&normal, &da[1][0]), dot(&normal, &da[0][1]), 0.0, 0.0])
+                }
+                Constraint::Fixed { index, .. } => {
+                    let mut row = [0.0; 4];
+                    row[index] = 1.0;
+                    Some(row)
+                }
+            };
+
+            let delta: Vector4<f64> = match row {
+                None => {
+                    let gram = jacobian * jacobian.transpose();
+                    -jacobian.transpose() * gram.lu().solve(&residual)?
+                }
+                Some(row) => {
+                    let matrix = Matrix4::from_fn(|r, c| if r < 3 { jacobian[(r, c)] } else { row[c] });
+                    -matrix.lu().solve(&Vector4::new(f[0], f[1], f[2], extra))?
+                }
+            };
+
+            x = self.clamp([0, 1, 2, 3].map(|k| x[k] + delta[k]));
+            if let Constraint::Fixed { index, value } = constraint {
+                x[index] = value;
+            }
+        }
+        None
+    }
+
+    fn clamp(&self, x: [f64; 4]) -> [f64; 4] {
+        [0, 1, 2, 3].map(|k| x[k].clamp(self.bounds[k][0], self.bounds[k][1]))
+    }
+
+    /// Midpoint of the two surface points
+    fn point(&self, x: [f64; 4]) -> [f64; 3] {
+        scale(&add(&self.a.evaluate(x[0], x[1]), &self.b.evaluate(x[2], x[3])), 0.5)
+    }
+}
+
+/// Parameter velocity (du, dv) with S_u du + S_v dv = tangent, by least
+/// squares
+fn tangent_parameters(su: &[f64; 3], sv: &[f64; 3], tangent: &[f64; 3]) -> Option<[f64; 2]> {
+    let (e, f, g) = (dot(su, su), dot(su, sv), dot(sv, sv));
+    let det = e * g - f * f;
+    if det.abs() < f64::MIN_POSITIVE {
+        return None;
+    }
+    let (a, b) = (dot(su, tangent), dot(sv, tangent));
+    Some([(g * a - f * b) / det, (e * b - f * a) / det])
+}
+
+/// Split a patch into four at the middle of its domain
+fn quarters(patch: &NURBSSurface) -> Vec<NURBSSurface> {
+    let ([u0, u1], [v0, v1]) = patch.domain();
+    let (left, right) = patch.split_u(0.5 * (u0 + u1));
+    [left, right]
+        .into_iter()
+        .flat_map(|half| {
+            let (bottom, top) = half.split_v(0.5 * (v0 + v1));
+            [bottom, top]
+        })
+        .collect()
+}
+
+/// Whether the surface closes on itself across the u (or v) boundaries
+fn is_periodic(surface: &NURBSSurface, along_u: bool) -> bool {
+    let ([u0, u1], [v0, v1]) = surface.domain();
+    let tolerance = 1e-9 * (1.0 + norm(&surface.bounds().size()));
+    (0..=6).all(|k| {
+        let (a, b) = if along_u {
+            let v = v0 + (v1 - v0) * k as f64 / 6.0;
+            (surface.evaluate(u0, v), surface.evaluate(u1, v))
+        } else {
+            let u = u0 + (u1 - u0) * k as f64 / 6.0;
+            (surface.evaluate(u, v0), surface.evaluate(u, v1))
+        };
+        distance(&a, &b) <= tolerance
+    })
+}
+
+/// Cubic through a branch with the fewest control points that keep it
+/// within `tolerance` of the points, or interpolating them
+fn fit_branch(points: &[[f64; 3]], closed: bool, tolerance: f64) -> Result<NURBSCurve, NurbsError> {
+    let mut rows: Vec<[f64; 3]> = points.to_vec();
+    if closed {
+        rows.push(points[0]);
+    }
+    let count = rows.len();
+
+    let data = Array2::from_shape_vec((count, 3), rows.concat()).expect("rows have three coordinates");
+    let degree = 3.min(count - 1);
+    let mut control = degree + 1;
+    while control < count {
+        let fit = NURBSCurve::approximate(&data, degree, control, Parameterization::ChordLength, None)?;
+        if fit.max_error <= tolerance {
+            return Ok(fit.curve);
+        }
+        control = (control * 3 / 2).max(control + 1);
+    }
+    NURBSCurve::interpolate(&data, degree, Parameterization::ChordLength, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn check_on_both(branch: &IntersectionBranch, a: &NURBSSurface, b: &NURBSSurface) {
+        for ((p, pa), pb) in branch.points.iter().zip(&branch.params_a).zip(&branch.params_b) {
+            assert!(distance(p, &a.evaluate(pa[0], pa[1])) < 1e-8);
+            assert!(distance(p, &b.evaluate(pb[0], pb[1])) < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_crossing_planes() {
+        let a = NURBSSurface::plane([-1.0, -1.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]);
+        let b = NURBSSurface::plane([0.0, -2.0, -1.0], [0.0, 4.0, 0.0], [0.0, 0.0, 2.0]);
+
+        let mut options = IntersectionOptions::new(0.1);
+        options.fit_tolerance = Some(1e-6);
+        let branches = a.intersect_surface(&b, &options).unwrap();
+        assert_eq!(branches.len(), 1);
+
+        let branch = &branches[0];
+        assert!(!branch.closed);
+        check_on_both(branch, &a, &b);
+        let (first, last) = (branch.points[0], branch.points[branch.points.len() - 1]);
+        assert_relative_eq!(first[1].abs(), 1.0, epsilon = 1e-9);
+        assert_relative_eq!(first[1] + last[1], 0.0, epsilon = 1e-9);
+
+        let curve = branch.curve.as_ref().unwrap();
+        let mid = curve.evaluate(0.5);
+        assert_relative_eq!(mid[0], 0.0, epsilon = 1e-9);
+        assert_relative_eq!(mid[2], 0.0, epsilon = 1e-9);
+
+        for step in [0.0, -0.1, f64::NAN] {
+            assert!(a.intersect_surface(&b, &IntersectionOptions::new(step)).is_err());
+        }
+        options.fit_tolerance = Some(0.0);
+        assert_eq!(
+            a.intersect_surface(&b, &options).unwrap_err(),
+            NurbsError::NonPositive { field: "fit tolerance", value: 0.0 }
+        );
+    }
+
+    #[test]
+    fn test_sphere_plane_loop() {
+        let sphere = NURBSSurface::sphere([0.0; 3], 1.0);
+        let plane = NURBSSurface::plane([-2.0, -2.0, 0.5], [4.0, 0.0, 0.0], [0.0, 4.0, 0.0]);
+
+        let branches = sphere.intersect_surface(&plane, &IntersectionOptions::new(0.05)).unwrap();
+        assert_eq!(branches.len(), 1);
+
+        let branch = &branches[0];
+        assert!(branch.closed);
+        check_on_both(branch, &sphere, &plane);
+        for p in &branch.points {
+            assert_relative_eq!(p[0].hypot(p[1]), 0.75f64.sqrt(), epsilon = 1e-9);
+            assert_relative_eq!(p[2], 0.5, epsilon = 1e-9);
+        }
+
+        // The loop goes all the way round
+        let length: f64 = (0..branch.points.len())
+            .map(|k| distance(&branch.points[k], &branch.points[(k + 1) % branch.points.len()]))
+            .sum();
+        assert_relative_eq!(length, std::f64::consts::TAU * 0.75f64.sqrt(), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_crossing_cylinders_give_two_loops() {
+        let big = NURBSSurface::cylinder([0.0, 0.0, -3.0], [0.0, 0.0, 1.0], 1.0, 6.0);
+        let small = NURBSSurface::cylinder([-3.0, 0.0, 0.0], [1.0, 0.0, 0.0], 0.5, 6.0);
+
+        let mut options = IntersectionOptions::new(0.05);
+        options.fit_tolerance = Some(1e-5);
+        let branches = big.intersect_surface(&small, &options).unwrap();
+        assert_eq!(branches.len(), 2);
+
+        let mut sides: Vec<f64> = Vec::new();
+        for branch in &branches {
+            assert!(branch.closed);
+            check_on_both(branch, &big, &small);
+            for p in &branch.points {
+                assert_relative_eq!(p[0].hypot(p[1]), 1.0, epsilon = 1e-9);
+                assert_relative_eq!(p[1].hypot(p[2]), 0.5, epsilon = 1e-9);
+            }
+
+            let curve = branch.curve.as_ref().unwrap();
+            for k in 0..=20 {
+                let p = curve.evaluate(k as f64 / 20.0);
+                assert!((p[0].hypot(p[1]) - 1.0).abs() < 1e-3);
+                assert!((p[1].hypot(p[2]) - 0.5).abs() < 1e-3);
+            }
+            sides.push(branch.points[0][0].signum());
+        }
+        sides.sort_by(f64::total_cmp);
+        assert_eq!(sides, vec![-1.0, 1.0]);
+    }
+}
@@ -1,7 +1,69 @@
-use ndarray::{Array2, Array3};
+use ndarray::{s, Array2, Array3};
 use rayon::prelude::*;
+use std::cell::RefCell;
+
+use crate::basis::CoxDeBoor;
+use crate::curve::{binomial, is_clamped};
+use crate::error::{validate_knots, validate_weights, NurbsError};
+
+thread_local! {
+    static SCRATCH: RefCell<EvalScratch> = RefCell::new(EvalScratch::default());
+}
+
+/// Reusable buffers for allocation-free point evaluation
+///
+/// Buffers grow to fit the largest degree seen and are then reused.
+#[derive(Debug, Clone, Default)]
+pub struct EvalScratch {
+    basis_u: Vec<f64>,
+    basis_v: Vec<f64>,
+    left: Vec<f64>,
+    right: Vec<f64>,
+}
+
+impl EvalScratch {
+    fn reserve(&mut self, len: usize) {
+        if self.left.len() < len {
+            for buffer in [&mut self.basis_u, &mut self.basis_v, &mut self.left, &mut self.right] {
+                buffer.resize(len, 0.0);
+            }
+        }
+    }
+}
+
+/// Map knots affinely so that the interval `from` becomes `to`
+pub(crate) fn remap_knots(knots: &[f64], from: [f64; 2], to: [f64; 2]) -> Vec<f64> {
+    let scale = (to[1] - to[0]) / (from[1] - from[0]);
+    knots
+        .iter()
+        .map(|&k| {
+            if k == from[1] {
+                to[1] // exact end value despite rounding
+            } else {
+                to[0] + (k - from[0]) * scale
+            }
+        })
+        .collect()
+}
+
+/// Knot span and non-zero basis functions at each parameter
+fn span_basis(params: &[f64], degree: usize, knots: &[f64]) -> Vec<(usize, Vec<f64>)> {
+    let mut left = vec![0.0; degree + 1];
+    let mut right = vec![0.0; degree + 1];
+
+    params
+        .iter()
+        .map(|&t| {
+            let span = CoxDeBoor::find_span(t, degree, knots);
+            let mut basis = vec![0.0; degree + 1];
+            CoxDeBoor::basis_funs_with(span, t, degree, knots, &mut basis, &mut left, &mut right);
+            (span, basis)
+        })
+        .collect()
+}
 
 /// NURBS surface representation
+#[derive(Debug, Clone)]
 pub struct NURBSSurface {
     pub degree_u: usize,
     pub degree_v: usize,
@@ -13,6 +75,9 @@ pub struct NURBSSurface {
 
 impl NURBSSurface {
     /// Create new NURBS surface
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_new` for the checks.
     pub fn new(
         degree_u: usize,
         degree_v: usize,
@@ -21,111 +86,276 @@ impl NURBSSurface {
         knots_u: Vec<f64>,
         knots_v: Vec<f64>,
     ) -> Self {
-        // Validate inputs
-        assert_eq!(control_points.shape()[2], 3, "Control points must be 3D");
-        assert_eq!(
-            control_points.shape()[0],
-            weights.shape()[0],
-            "Control points and weights must match in u dimension"
-        );
-        assert_eq!(
-            control_points.shape()[1],
-            weights.shape()[1],
-            "Control points and weights must match in v dimension"
-        );
-        assert_eq!(
-            knots_u.len(),
-            control_points.shape()[0] + degree_u + 1,
-            "Invalid knot vector length in u"
-        );
-        assert_eq!(
-            knots_v.len(),
-            control_points.shape()[1] + degree_v + 1,
-            "Invalid knot vector length in v"
-        );
+        Self::try_new(degree_u, degree_v, control_points, weights, knots_u, knots_v)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create new NURBS surface, validating the inputs
+    ///
+    /// Checks control point dimension, weight shape, knot vector lengths,
+    /// knot monotonicity, degree against control count, positive weights and
+    /// the absence of NaN or infinite values.
+    pub fn try_new(
+        degree_u: usize,
+        degree_v: usize,
+        control_points: Array3<f64>,
+        weights: Array2<f64>,
+        knots_u: Vec<f64>,
+        knots_v: Vec<f64>,
+    ) -> Result<Self, NurbsError> {
+        let shape = control_points.shape();
+        if shape[2] != 3 {
+            return Err(NurbsError::InvalidDimension { expected: 3, found: shape[2] });
+        }
+        if weights.shape() != &shape[..2] {
+            return Err(NurbsError::WeightShapeMismatch {
+                expected: shape[..2].to_vec(),
+                found: weights.shape().to_vec(),
+            });
+        }
 
-        Self {
+        validate_knots("u", &knots_u, degree_u, shape[0])?;
+        validate_knots("v", &knots_v, degree_v, shape[1])?;
+
+        if control_points.iter().any(|c| !c.is_finite()) {
+            return Err(NurbsError::NonFinite { field: "control points" });
+        }
+        validate_weights(weights.iter())?;
+
+        Ok(Self {
             degree_u,
             degree_v,
             control_points,
             weights,
             knots_u,
             knots_v,
-        }
+        })
     }
 
     /// Evaluate surface at parameter (u, v)
+    ///
+    /// Only the (p+1) x (q+1) control points of the active knot span are
+    /// visited. Scratch buffers are kept per thread, so repeated calls do not
+    /// allocate.
     pub fn evaluate(&self, u: f64, v: f64) -> [f64; 3] {
-        let u_res = self.control_points.shape()[0];
-        let v_res = self.control_points.shape()[1];
+        SCRATCH.with(|scratch| self.evaluate_with(u, v, &mut scratch.borrow_mut()))
+    }
 
-        // Evaluate basis functions
-        let mut basis_u = vec![0.0; u_res];
-        let mut basis_v = vec![0.0; v_res];
+    /// Evaluate surface at (u, v) using caller-owned scratch buffers
+    pub fn evaluate_with(&self, u: f64, v: f64, scratch: &mut EvalScratch) -> [f64; 3] {
+        let p = self.degree_u;
+        let q = self.degree_v;
+        scratch.reserve(p.max(q) + 1);
 
-        super::basis::CoxDeBoor::evaluate_all(u, &self.knots_u, self.degree_u, &mut basis_u);
-        super::basis::CoxDeBoor::evaluate_all(v, &self.knots_v, self.degree_v, &mut basis_v);
+        let span_u = CoxDeBoor::find_span(u, p, &self.knots_u);
+        let span_v = CoxDeBoor::find_span(v, q, &self.knots_v);
 
-        // Compute rational basis functions
-        let mut weight_sum = 0.0;
-        for i in 0..u_res {
-            for j in 0..v_res {
-                weight_sum += basis_u[i] * basis_v[j] * self.weights[[i, j]];
-            }
-        }
+        let EvalScratch { basis_u, basis_v, left, right } = scratch;
+        CoxDeBoor::basis_funs_with(span_u, u, p, &self.knots_u, basis_u, left, right);
+        CoxDeBoor::basis_funs_with(span_v, v, q, &self.knots_v, basis_v, left, right);
+
+        self.combine(span_u, &basis_u[..=p], span_v, &basis_v[..=q])
+    }
 
-        let mut point = [0.0, 0.0, 0.0];
+    /// Rational combination of the control points of one span
+    #[allow(clippy::needless_range_loop)]
+    fn combine(&self, span_u: usize, basis_u: &[f64], span_v: usize, basis_v: &[f64]) -> [f64; 3] {
+        let i0 = span_u + 1 - basis_u.len();
+        let j0 = span_v + 1 - basis_v.len();
 
-        for i in 0..u_res {
-            for j in 0..v_res {
-                let rational_basis = (basis_u[i] * basis_v[j] * self.weights[[i, j]]) / weight_sum;
+        let mut point = [0.0; 3];
+        let mut weight_sum = 0.0;
+
+        for (a, &nu) in basis_u.iter().enumerate() {
+            for (b, &nv) in basis_v.iter().enumerate() {
+                let (i, j) = (i0 + a, j0 + b);
+                let nw = nu * nv * self.weights[[i, j]];
+                weight_sum += nw;
 
                 for k in 0..3 {
-                    point[k] += rational_basis * self.control_points[[i, j, k]];
+                    point[k] += nw * self.control_points[[i, j, k]];
                 }
             }
         }
 
+        for value in point.iter_mut() {
+            *value /= weight_sum;
+        }
+
         point
     }
 
+    /// Evaluate with parameters normalized to [0, 1] over the knot domain
+    pub fn evaluate_normalized(&self, s: f64, t: f64) -> [f64; 3] {
+        let (u, v) = self.normalized_to_domain(s, t);
+        self.evaluate(u, v)
+    }
+
+    /// Parameter intervals ([u_min, u_max], [v_min, v_max]) of the surface
+    ///
+    /// These are [knots_u[p], knots_u[n]] and [knots_v[q], knots_v[m]], which
+    /// need not be [0, 1] (e.g. surfaces imported from STEP data).
+    pub fn domain(&self) -> ([f64; 2], [f64; 2]) {
+        let (u_res, v_res) = self.dimensions();
+        (
+            [self.knots_u[self.degree_u], self.knots_u[u_res]],
+            [self.knots_v[self.degree_v], self.knots_v[v_res]],
+        )
+    }
+
+    /// Map normalized parameters in [0, 1]^2 to the knot domain
+    pub fn normalized_to_domain(&self, s: f64, t: f64) -> (f64, f64) {
+        let ([u0, u1], [v0, v1]) = self.domain();
+        (u0 + s * (u1 - u0), v0 + t * (v1 - v0))
+    }
+
+    /// Map domain parameters to normalized parameters in [0, 1]^2
+    pub fn domain_to_normalized(&self, u: f64, v: f64) -> (f64, f64) {
+        let ([u0, u1], [v0, v1]) = self.domain();
+        ((u - u0) / (u1 - u0), (v - v0) / (v1 - v0))
+    }
+
+    /// Copy of the surface with knots mapped affinely onto new domains
+    ///
+    /// The geometry is unchanged; only the parameterization is rescaled.
+    ///
+    /// # Panics
+    /// Panics if a target range is empty.
+    pub fn reparameterize(&self, u_range: [f64; 2], v_range: [f64; 2]) -> NURBSSurface {
+        assert!(
+            u_range[0] < u_range[1] && v_range[0] < v_range[1],
+            "reparameterization ranges must be non-empty"
+        );
+        let (u_domain, v_domain) = self.domain();
+        let mut surface = self.clone();
+        surface.knots_u = remap_knots(&self.knots_u, u_domain, u_range);
+        surface.knots_v = remap_knots(&self.knots_v, v_domain, v_range);
+        surface
+    }
+
     /// Batch evaluation (parallelized)
     pub fn evaluate_batch(&self, uv_pairs: &[[f64; 2]]) -> Vec<[f64; 3]> {
         uv_pairs
             .par_iter()
-            .map(|&[u, v]| self.evaluate(u, v))
+            .map_init(EvalScratch::default, |scratch, &[u, v]| self.evaluate_with(u, v, scratch))
             .collect()
     }
 
-    /// Evaluate on uniform grid (for tessellation)
+    /// Evaluate on uniform grid over the knot domain (for tessellation)
+    ///
+    /// Basis functions are computed once per grid row and column and shared
+    /// by every point on it.
     pub fn evaluate_grid(&self, u_samples: usize, v_samples: usize) -> Array3<f64> {
-        let u_step = 1.0 / (u_samples - 1) as f64;
-        let v_step = 1.0 / (v_samples - 1) as f64;
+        let ([u0, u1], [v0, v1]) = self.domain();
+        let u_step = (u1 - u0) / (u_samples - 1) as f64;
+        let v_step = (v1 - v0) / (v_samples - 1) as f64;
 
-        // Compute all (u, v) pairs for the grid
-        let indices: Vec<(usize, usize)> = (0..u_samples)
-            .flat_map(|i| (0..v_samples).map(move |j| (i, j)))
-            .collect();
+        let us: Vec<f64> = (0..u_samples).map(|i| u0 + i as f64 * u_step).collect();
+        let vs: Vec<f64> = (0..v_samples).map(|j| v0 + j as f64 * v_step).collect();
+        let basis_u = span_basis(&us, self.degree_u, &self.knots_u);
+        let basis_v = span_basis(&vs, self.degree_v, &self.knots_v);
 
-        // Parallel evaluation of all grid points
-        let points: Vec<[f64; 3]> = indices.par_iter()
-            .map(|&(i, j)| {
-                let u = i as f64 * u_step;
-                let v = j as f64 * v_step;
-                self.evaluate(u, v)
+        // Parallel evaluation, one grid row per task
+        let rows: Vec<Vec<[f64; 3]>> = basis_u
+            .par_iter()
+            .map(|(span_u, nu)| {
+                basis_v
+                    .iter()
+                    .map(|(span_v, nv)| self.combine(*span_u, nu, *span_v, nv))
+                    .collect()
             })
             .collect();
 
-        // Convert flat Vec<[f64; 3]> into Array3
         let mut grid = Array3::zeros((u_samples, v_samples, 3));
-        for ((i, j), point) in indices.into_iter().zip(points.into_iter()) {
-            for k in 0..3 {
-                grid[[i, j, k]] = point[k];
+        for (i, row) in rows.iter().enumerate() {
+            for (j, point) in row.iter().enumerate() {
+                for k in 0..3 {
+                    grid[[i, j, k]] = point[k];
+                }
             }
         }
         grid
     }
 
+    /// Rational partial derivatives S_kl(u, v) for k + l <= order
+    ///
+    /// `ders[k][l]` is the k-th derivative in u and l-th derivative in v, so
+    /// `ders[0][0]` is the surface point. Entries with k + l > order are zero.
+    #[allow(clippy::needless_range_loop)]
+    pub fn derivatives(&self, u: f64, v: f64, order: usize) -> Vec<Vec<[f64; 3]>> {
+        let p = self.degree_u;
+        let q = self.degree_v;
+        let span_u = CoxDeBoor::find_span(u, p, &self.knots_u);
+        let span_v = CoxDeBoor::find_span(v, q, &self.knots_v);
+
+        let mut ders_u = vec![vec![0.0; p + 1]; order + 1];
+        let mut ders_v = vec![vec![0.0; q + 1]; order + 1];
+        CoxDeBoor::evaluate_derivatives(u, &self.knots_u, p, order, &mut ders_u);
+        CoxDeBoor::evaluate_derivatives(v, &self.knots_v, q, order, &mut ders_v);
+
+        // Derivatives of the homogeneous surface: (w * S)_kl and w_kl
+        let mut a_ders = vec![vec![[0.0; 3]; order + 1]; order + 1];
+        let mut w_ders = vec![vec![0.0; order + 1]; order + 1];
+
+        for k in 0..=order.min(p) {
+            for l in 0..=(order - k).min(q) {
+                for i in 0..=p {
+                    let iu = span_u - p + i;
+                    for j in 0..=q {
+                        let jv = span_v - q + j;
+                        let nw = ders_u[k][i] * ders_v[l][j] * self.weights[[iu, jv]];
+                        w_ders[k][l] += nw;
+
+                        for d in 0..3 {
+                            a_ders[k][l][d] += nw * self.control_points[[iu, jv, d]];
+                        }
+                    }
+                }
+            }
+        }
+
+        // Quotient rule (NURBS Book, A4.4)
+        let mut ders = vec![vec![[0.0; 3]; order + 1]; order + 1];
+        for k in 0..=order {
+            for l in 0..=(order - k) {
+                let mut val = a_ders[k][l];
+
+                for j in 1..=l {
+                    let c = binomial(l, j) * w_ders[0][j];
+                    for d in 0..3 {
+                        val[d] -= c * ders[k][l - j][d];
+                    }
+                }
+
+                for i in 1..=k {
+                    let c = binomial(k, i) * w_ders[i][0];
+                    for d in 0..3 {
+                        val[d] -= c * ders[k - i][l][d];
+                    }
+
+                    let mut val2 = [0.0; 3];
+                    for j in 1..=l {
+                        let c = binomial(l, j) * w_ders[i][j];
+                        for d in 0..3 {
+                            val2[d] += c * ders[k - i][l - j][d];
+                        }
+                    }
+
+                    let c = binomial(k, i);
+                    for d in 0..3 {
+                        val[d] -= c * val2[d];
+                    }
+                }
+
+                for d in 0..3 {
+                    ders[k][l][d] = val[d] / w_ders[0][0];
+                }
+            }
+        }
+
+        ders
+    }
+
     /// Get control point at index (i, j)
     pub fn control_point(&self, i: usize, j: usize) -> [f64; 3] {
         [
@@ -144,6 +374,150 @@ impl NURBSSurface {
     pub fn dimensions(&self) -> (usize, usize) {
         (self.control_points.shape()[0], self.control_points.shape()[1])
     }
+
+    /// Whether both knot vectors are clamped (end multiplicity degree + 1)
+    pub fn is_clamped(&self) -> bool {
+        is_clamped(&self.knots_u, self.degree_u) && is_clamped(&self.knots_v, self.degree_v)
+    }
+
+    /// Homogeneous control points [w*x, w*y, w*z, w]
+    pub fn homogeneous(&self) -> Array3<f64> {
+        let (u_res, v_res) = self.dimensions();
+        let mut pw = Array3::zeros((u_res, v_res, 4));
+
+        for i in 0..u_res {
+            for j in 0..v_res {
+                let w = self.weights[[i, j]];
+                for k in 0..3 {
+                    pw[[i, j, k]] = w * self.control_points[[i, j, k]];
+                }
+                pw[[i, j, 3]] = w;
+            }
+        }
+
+        pw
+    }
+
+    /// Build a surface from homogeneous control points [u_res, v_res, 4]
+    ///
+    /// # Panics
+    /// Panics if the inputs are invalid; see `try_from_homogeneous`.
+    pub fn from_homogeneous(
+        degree_u: usize,
+        degree_v: usize,
+        pw: &Array3<f64>,
+        knots_u: Vec<f64>,
+        knots_v: Vec<f64>,
+    ) -> Self {
+        Self::try_from_homogeneous(degree_u, degree_v, pw, knots_u, knots_v).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Build a surface from homogeneous control points, validating the
+    /// result as `try_new` does
+    pub fn try_from_homogeneous(
+        degree_u: usize,
+        degree_v: usize,
+        pw: &Array3<f64>,
+        knots_u: Vec<f64>,
+        knots_v: Vec<f64>,
+    ) -> Result<Self, NurbsError> {
+        let (u_res, v_res) = (pw.shape()[0], pw.shape()[1]);
+        let mut control_points = Array3::zeros((u_res, v_res, 3));
+        let mut weights = Array2::zeros((u_res, v_res));
+
+        for i in 0..u_res {
+            for j in 0..v_res {
+                let w = pw[[i, j, 3]];
+                weights[[i, j]] = w;
+                for k in 0..3 {
+                    control_points[[i, j, k]] = pw[[i, j, k]] / w;
+                }
+            }
+        }
+
+        Self::try_new(degree_u, degree_v, control_points, weights, knots_u, knots_v)
+    }
+
+    /// Degree and knot vector in the given direction
+    pub(crate) fn basis_in(&self, dir: Direction) -> (usize, &[f64]) {
+        match dir {
+            Direction::U => (self.degree_u, &self.knots_u),
+            Direction::V => (self.degree_v, &self.knots_v),
+        }
+    }
+
+    /// Apply a curve algorithm to every row of homogeneous control points
+    ///
+    /// `f` receives the knot vector, degree and one control row running in
+    /// `dir`, and returns the new knots, degree and row. All rows share a
+    /// knot vector, so the result of the first row defines the surface.
+    pub(crate) fn map_rows<F>(&self, dir: Direction, f: F) -> NURBSSurface
+    where
+        F: Fn(&[f64], usize, &Array2<f64>) -> (Vec<f64>, usize, Array2<f64>),
+    {
+        self.try_map_rows(dir, |knots, degree, row| Some(f(knots, degree, row)))
+            .expect("row operation cannot fail")
+    }
+
+    /// Like `map_rows`, but the whole operation fails if any row fails
+    pub(crate) fn try_map_rows<F>(&self, dir: Direction, f: F) -> Option<NURBSSurface>
+    where
+        F: Fn(&[f64], usize, &Array2<f64>) -> Option<(Vec<f64>, usize, Array2<f64>)>,
+    {
+        let pw = self.homogeneous();
+        let (u_res, v_res) = self.dimensions();
+        let (degree, knots) = self.basis_in(dir);
+
+        let rows: Vec<(Vec<f64>, usize, Array2<f64>)> = match dir {
+            Direction::U => (0..v_res)
+                .map(|j| f(knots, degree, &pw.slice(s![.., j, ..]).to_owned()))
+                .collect::<Option<_>>()?,
+            Direction::V => (0..u_res)
+                .map(|i| f(knots, degree, &pw.slice(s![i, .., ..]).to_owned()))
+                .collect::<Option<_>>()?,
+        };
+
+        let (new_knots, new_degree) = (rows[0].0.clone(), rows[0].1);
+        let len = rows[0].2.shape()[0];
+
+        let surface = match dir {
+            Direction::U => {
+                let mut new_pw = Array3::zeros((len, v_res, 4));
+                for (j, (_, _, row)) in rows.iter().enumerate() {
+                    new_pw.slice_mut(s![.., j, ..]).assign(row);
+                }
+                Self::from_homogeneous(
+                    new_degree,
+                    self.degree_v,
+                    &new_pw,
+                    new_knots,
+                    self.knots_v.clone(),
+                )
+            }
+            Direction::V => {
+                let mut new_pw = Array3::zeros((u_res, len, 4));
+                for (i, (_, _, row)) in rows.iter().enumerate() {
+                    new_pw.slice_mut(s![i, .., ..]).assign(row);
+                }
+                Self::from_homogeneous(
+                    self.degree_u,
+                    new_degree,
+                    &new_pw,
+                    self.knots_u.clone(),
+                    new_knots,
+                )
+            }
+        };
+
+        Some(surface)
+    }
+}
+
+/// Parametric direction of a tensor-product surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    U,
+    V,
 }
 
 #[cfg(test)]
@@ -209,6 +583,176 @@ mod tests {
         assert_relative_eq!(point[2], 0.0, epsilon = 1e-6);
     }
 
+    #[test]
+    fn test_derivatives_match_finite_differences() {
+        // Rational surface: bump the weights of the interior control points
+        let mut surface = create_flat_plane();
+        for i in 1..4 {
+            for j in 1..4 {
+                surface.weights[[i, j]] = 1.5 + 0.1 * (i + j) as f64;
+                surface.control_points[[i, j, 2]] = 0.2 * (i * j) as f64;
+            }
+        }
+
+        let (u, v) = (0.3, 0.6);
+        let h = 1e-5;
+        let ders = surface.derivatives(u, v, 2);
+
+        let p = surface.evaluate(u, v);
+        let pu = surface.evaluate(u + h, v);
+        let mu = surface.evaluate(u - h, v);
+        let pv = surface.evaluate(u, v + h);
+        let mv = surface.evaluate(u, v - h);
+        let pp = surface.evaluate(u + h, v + h);
+        let pm = surface.evaluate(u + h, v - h);
+        let mp = surface.evaluate(u - h, v + h);
+        let mm = surface.evaluate(u - h, v - h);
+
+        for d in 0..3 {
+            assert_relative_eq!(ders[0][0][d], p[d], epsilon = 1e-12);
+            assert_relative_eq!(ders[1][0][d], (pu[d] - mu[d]) / (2.0 * h), epsilon = 1e-6);
+            assert_relative_eq!(ders[0][1][d], (pv[d] - mv[d]) / (2.0 * h), epsilon = 1e-6);
+            assert_relative_eq!(ders[2][0][d], (pu[d] - 2.0 * p[d] + mu[d]) / (h * h), epsilon = 1e-3);
+            assert_relative_eq!(ders[0][2][d], (pv[d] - 2.0 * p[d] + mv[d]) / (h * h), epsilon = 1e-3);
+            assert_relative_eq!(
+                ders[1][1][d],
+                (pp[d] - pm[d] - mp[d] + mm[d]) / (4.0 * h * h),
+                epsilon = 1e-3
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_new_rejects_invalid_input() {
+        let plane = create_flat_plane();
+        let build = |weights: Array2<f64>, knots_u: Vec<f64>| {
+            NURBSSurface::try_new(
+                3,
+                3,
+                plane.control_points.clone(),
+                weights,
+                knots_u,
+                plane.knots_v.clone(),
+            )
+        };
+
+        assert!(build(plane.weights.clone(), plane.knots_u.clone()).is_ok());
+
+        let short = vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0];
+        assert_eq!(
+            build(plane.weights.clone(), short).unwrap_err(),
+            NurbsError::InvalidKnotLength { axis: "u", expected: 9, found: 8 }
+        );
+
+        let decreasing = vec![0.0, 0.0, 0.0, 0.0, 0.7, 0.5, 1.0, 1.0, 1.0];
+        assert_eq!(
+            build(plane.weights.clone(), decreasing).unwrap_err(),
+            NurbsError::NonMonotonicKnots { axis: "u", index: 5 }
+        );
+
+        let mut weights = plane.weights.clone();
+        weights[[1, 2]] = 0.0;
+        assert_eq!(
+            build(weights, plane.knots_u.clone()).unwrap_err(),
+            NurbsError::NonPositiveWeight { index: 7, weight: 0.0 }
+        );
+
+        let mut weights = plane.weights.clone();
+        weights[[0, 0]] = f64::NAN;
+        assert_eq!(
+            build(weights, plane.knots_u.clone()).unwrap_err(),
+            NurbsError::NonFinite { field: "weights" }
+        );
+
+        assert_eq!(
+            build(Array2::ones((5, 4)), plane.knots_u.clone()).unwrap_err(),
+            NurbsError::WeightShapeMismatch { expected: vec![5, 5], found: vec![5, 4] }
+        );
+
+        let too_high = NURBSSurface::try_new(
+            5,
+            3,
+            plane.control_points.clone(),
+            plane.weights.clone(),
+            vec![0.0; 11],
+            plane.knots_v.clone(),
+        );
+        assert_eq!(
+            too_high.unwrap_err(),
+            NurbsError::DegreeTooHigh { axis: "u", degree: 5, control_points: 5 }
+        );
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn test_span_local_evaluation_matches_dense_sum() {
+        let mut surface = create_flat_plane();
+        for i in 0..5 {
+            for j in 0..5 {
+                surface.weights[[i, j]] = 1.0 + 0.3 * ((i * j) % 3) as f64;
+                surface.control_points[[i, j, 2]] = (i as f64 - j as f64 * 0.5).sin();
+            }
+        }
+
+        let mut basis_u = vec![0.0; 5];
+        let mut basis_v = vec![0.0; 5];
+        let mut scratch = EvalScratch::default();
+        let grid = surface.evaluate_grid(7, 6);
+
+        for i in 0..7 {
+            for j in 0..6 {
+                let (u, v) = (i as f64 / 6.0, j as f64 / 5.0);
+
+                // Reference: rational sum over every control point
+                CoxDeBoor::evaluate_all(u, &surface.knots_u, 3, &mut basis_u);
+                CoxDeBoor::evaluate_all(v, &surface.knots_v, 3, &mut basis_v);
+                let mut expected = [0.0; 3];
+                let mut weight_sum = 0.0;
+                for a in 0..5 {
+                    for b in 0..5 {
+                        let nw = basis_u[a] * basis_v[b] * surface.weights[[a, b]];
+                        weight_sum += nw;
+                        for k in 0..3 {
+                            expected[k] += nw * surface.control_points[[a, b, k]];
+                        }
+                    }
+                }
+
+                let point = surface.evaluate_with(u, v, &mut scratch);
+                for k in 0..3 {
+                    assert_relative_eq!(point[k], expected[k] / weight_sum, epsilon = 1e-12);
+                    assert_relative_eq!(grid[[i, j, k]], point[k], epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_covers_knot_domain() {
+        // Same plane with knots scaled to [0, 3] x [-1, 1]
+        let plane = create_flat_plane();
+        let surface = NURBSSurface::new(
+            3,
+            3,
+            plane.control_points.clone(),
+            plane.weights.clone(),
+            plane.knots_u.iter().map(|k| 3.0 * k).collect(),
+            plane.knots_v.iter().map(|k| 2.0 * k - 1.0).collect(),
+        );
+
+        assert_eq!(surface.domain(), ([0.0, 3.0], [-1.0, 1.0]));
+
+        let grid = surface.evaluate_grid(3, 3);
+        assert_relative_eq!(grid[[2, 2, 0]], 1.0, epsilon = 1e-12);
+        assert_relative_eq!(grid[[2, 2, 1]], 1.0, epsilon = 1e-12);
+        assert_relative_eq!(grid[[1, 1, 0]], 0.5, epsilon = 1e-12);
+
+        let p = surface.evaluate_normalized(0.5, 0.5);
+        let q = surface.evaluate(1.5, 0.0);
+        assert_eq!(p, q);
+        assert_eq!(surface.domain_to_normalized(1.5, 0.0), (0.5, 0.5));
+    }
+
     #[test]
     fn test_batch_evaluation() {
         let surface = create_flat_plane();
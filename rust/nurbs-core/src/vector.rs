@@ -0,0 +1,69 @@
+//! Small helpers for 3D vectors stored as `[f64; 3]`
+
+/// Vector sum a + b
+pub(crate) fn add(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// Vector difference a - b
+pub(crate) fn sub(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Scalar multiple s * a
+pub(crate) fn scale(a: &[f64; 3], s: f64) -> [f64; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+/// Dot product of 3D vectors
+pub(crate) fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Cross product of 3D vectors
+pub(crate) fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Euclidean length
+pub(crate) fn norm(a: &[f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Unit vector in the direction of a, or None if a is (near) zero
+pub(crate) fn normalize(a: &[f64; 3]) -> Option<[f64; 3]> {
+    let length = norm(a);
+    if length > 1e-10 {
+        Some(scale(a, 1.0 / length))
+    } else {
+        None
+    }
+}
+
+/// Euclidean distance between two points
+pub(crate) fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    norm(&sub(a, b))
+}
+
+/// Distance from `p` to the segment [a, b]
+pub(crate) fn segment_distance(p: &[f64; 3], a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    let ab = sub(b, a);
+    let length = dot(&ab, &ab);
+    let t = if length > 0.0 { (dot(&sub(p, a), &ab) / length).clamp(0.0, 1.0) } else { 0.0 };
+    distance(p, &add(a, &scale(&ab, t)))
+}
+
+/// Unit vector perpendicular to a unit vector a, built from the coordinate
+/// axis least aligned with it
+pub(crate) fn perpendicular(a: &[f64; 3]) -> [f64; 3] {
+    let axis = (0..3)
+        .min_by(|&i, &j| a[i].abs().total_cmp(&a[j].abs()))
+        .unwrap();
+    let mut seed = [0.0; 3];
+    seed[axis] = 1.0;
+    normalize(&cross(&cross(a, &seed), a)).unwrap()
+}
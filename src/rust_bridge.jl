@@ -39,6 +39,19 @@ function check_library()
     end
 end
 
+# Status codes returned by nurbs_create_checked (see rust/nurbs-core/src/ffi.rs)
+const NURBS_ERROR_MESSAGES = Dict{Cint, String}(
+    1 => "invalid argument (null pointer or negative size)",
+    2 => "control points must be 3D",
+    3 => "weights must match control point dimensions",
+    4 => "invalid knot vector length",
+    5 => "knot vector must be non-decreasing",
+    6 => "knot vector spans an empty parameter domain",
+    7 => "degree too high for the number of control points",
+    8 => "weights must be positive",
+    9 => "non-finite value in input",
+)
+
 # Opaque handle type
 mutable struct NURBSSurfaceHandle
     ptr::Ptr{Cvoid}
@@ -91,18 +104,67 @@ function nurbs_create(
     cp_flat = vec(permutedims(control_points, [3, 2, 1]))
     w_flat = vec(weights')
 
+    error_code = Ref{Cint}(0)
+
     ptr = ccall(
-        (:nurbs_create, NURBS_LIB),
+        (:nurbs_create_checked, NURBS_LIB),
         Ptr{Cvoid},
-        (Cint, Cint, Cint, Cint, Ptr{Float64}, Ptr{Float64}, Ptr{Float64}, Ptr{Float64}, Cint, Cint),
+        (Cint, Cint, Cint, Cint, Ptr{Float64}, Ptr{Float64}, Ptr{Float64}, Ptr{Float64}, Cint, Cint, Ref{Cint}),
         degree_u, degree_v, u_res, v_res,
         cp_flat, w_flat, knots_u, knots_v,
-        length(knots_u), length(knots_v)
+        length(knots_u), length(knots_v), error_code
     )
 
+    if ptr == C_NULL
+        message = get(NURBS_ERROR_MESSAGES, error_code[], "unknown error")
+        error("Failed to create NURBS surface: $message (code $(error_code[]))")
+    end
+
     return NURBSSurfaceHandle(ptr)
 end
 
+"""
+    nurbs_domain(handle)
+
+Parameter domain of the surface, as given by its knot vectors
+
+# Returns
+- `Tuple{NTuple{2, Float64}, NTuple{2, Float64}}`: ((u_min, u_max), (v_min, v_max))
+"""
+function nurbs_domain(handle::NURBSSurfaceHandle)
+    output = zeros(Float64, 4)
+
+    ccall(
+        (:nurbs_domain, NURBS_LIB),
+        Cvoid,
+        (Ptr{Cvoid}, Ptr{Float64}),
+        handle.ptr, output
+    )
+
+    return ((output[1], output[2]), (output[3], output[4]))
+end
+
+"""
+    nurbs_set_normalized(handle, normalized)
+
+Choose whether (u, v) arguments are knot-domain values (default) or
+normalized [0, 1] parameters mapped onto the knot domain
+
+# Arguments
+- `handle::NURBSSurfaceHandle`: Surface handle
+- `normalized::Bool`: Interpret parameters in [0, 1]
+"""
+function nurbs_set_normalized(handle::NURBSSurfaceHandle, normalized::Bool)
+    ccall(
+        (:nurbs_set_normalized, NURBS_LIB),
+        Cvoid,
+        (Ptr{Cvoid}, Cint),
+        handle.ptr, normalized ? 1 : 0
+    )
+
+    return handle
+end
+
 """
     nurbs_evaluate(handle, u, v)
 
@@ -110,8 +172,8 @@ Evaluate NURBS surface at parameter (u, v)
 
 # Arguments
 - `handle::NURBSSurfaceHandle`: Surface handle from nurbs_create
-- `u::Float64`: Parameter in u direction [0, 1]
-- `v::Float64`: Parameter in v direction [0, 1]
+- `u::Float64`: Parameter in u direction (knot domain, or [0, 1] if normalized)
+- `v::Float64`: Parameter in v direction (knot domain, or [0, 1] if normalized)
 
 # Returns
 - `Vector{Float64}`: Point [x, y, z]
@@ -160,7 +222,7 @@ end
 """
     nurbs_evaluate_grid(handle, u_samples, v_samples)
 
-Evaluate surface on uniform grid
+Evaluate surface on uniform grid spanning the knot domain
 
 # Arguments
 - `handle::NURBSSurfaceHandle`: Surface handle
@@ -236,6 +298,47 @@ function nurbs_curvature(handle::NURBSSurfaceHandle, u::Float64, v::Float64)
     return (output[1], output[2])
 end
 
+"""
+    nurbs_curvature_info(handle, u, v)
+
+Full differential-geometry report at (u, v)
+
+Curvatures are signed with respect to the returned normal, with k1 >= k2.
+
+# Arguments
+- `handle::NURBSSurfaceHandle`: Surface handle
+- `u::Float64`: Parameter in u direction
+- `v::Float64`: Parameter in v direction
+
+# Returns
+- `NamedTuple`: `first_form` (E, F, G), `second_form` (L, M, N), `gaussian`,
+  `mean`, `k1`, `k2`, principal directions `dir1` and `dir2`, `normal`, and
+  `umbilic::Bool`
+"""
+function nurbs_curvature_info(handle::NURBSSurfaceHandle, u::Float64, v::Float64)
+    output = zeros(Float64, 20)
+
+    ccall(
+        (:nurbs_curvature_info, NURBS_LIB),
+        Cvoid,
+        (Ptr{Cvoid}, Float64, Float64, Ptr{Float64}),
+        handle.ptr, u, v, output
+    )
+
+    return (
+        first_form = (output[1], output[2], output[3]),
+        second_form = (output[4], output[5], output[6]),
+        gaussian = output[7],
+        mean = output[8],
+        k1 = output[9],
+        k2 = output[10],
+        dir1 = output[11:13],
+        dir2 = output[14:16],
+        normal = output[17:19],
+        umbilic = output[20] != 0.0,
+    )
+end
+
 """
     nurbs_free(handle)
 